@@ -0,0 +1,27 @@
+//! Error types.
+
+use std::{io::Error as IoError, result::Result as StdResult};
+
+use notify::Error as NotifyError;
+use serde_json::Error as JsonError;
+use thiserror::Error as ThisError;
+
+/// Represents an error in loading configurations.
+#[non_exhaustive]
+#[derive(Debug, ThisError)]
+pub enum Error {
+    /// Failed to read a file or directory.
+    #[error("I/O error: {0}")]
+    Io(#[from] IoError),
+
+    /// Invalid configuration JSON.
+    #[error("Invalid configuration: {0}")]
+    Json(#[from] JsonError),
+
+    /// Failed to watch file system.
+    #[error("Watch error: {0}")]
+    Watch(#[from] NotifyError),
+}
+
+/// Result type for configurations.
+pub type Result<T> = StdResult<T, Error>;
@@ -1,14 +1,23 @@
+pub mod error;
 pub mod vrchat;
 pub mod xsoverlay;
 
-/// Prelude module, prefixed with `Vrc` and `Xso` to avoid identifier conflict.
+/// Prelude module, prefixed with `Config`, `Vrc` and `Xso` to avoid identifier conflict.
 pub mod prelude {
+    pub use crate::error::{Error as ConfigError, Result as ConfigResult};
     pub use crate::vrchat::{
         config::{
             Configuration as VrcConfiguration, Parameter as VrcParameter,
             ParameterAddress as VrcParameterAddress, ParameterDataType as VrcParameterDataType,
+            UnknownFields as VrcUnknownFields,
         },
-        dir::VrcDirs,
+        dir::{OscDir as VrcOscDir, VrcDirs},
+        state::{ParameterChange as VrcParameterChange, ParameterState as VrcParameterState},
+        value::{
+            ParameterValue as VrcParameterValue, ValueError as VrcValueError,
+            ValueResult as VrcValueResult,
+        },
+        watch::{ConfigEvent as VrcConfigEvent, ConfigWatcher as VrcConfigWatcher},
     };
 
     pub use crate::xsoverlay::{
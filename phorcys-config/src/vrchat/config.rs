@@ -1,4 +1,29 @@
-use serde::{Deserialize, Serialize};
+use crate::error::Result;
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    fs::{read, write},
+    hash::{Hash, Hasher},
+    mem::replace,
+    path::Path,
+    result::Result as StdResult,
+};
+
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::{ser::PrettyFormatter, Map, Value as JsonValue};
+
+/// UTF-8 BOM, which may precede configuration files. It is stripped on read and not written.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Indentation of written configuration files.
+const INDENT: &[u8] = b"    ";
+
+/// Prefix of avatar parameter addresses.
+const PARAMETER_ADDRESS_PREFIX: &str = "/avatar/parameters/";
 
 /// Represents the whole configuration of an avatar.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -6,14 +31,122 @@ pub struct Configuration {
     pub id: String,
     pub name: String,
     pub parameters: Vec<Parameter>,
+
+    /// Fields not known by this crate, kept as they are.
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
+}
+
+impl Configuration {
+    /// Parses configuration JSON.
+    /// Leading UTF-8 BOM is skipped.
+    pub fn from_slice(bytes: &[u8]) -> Result<Configuration> {
+        let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// Loads configuration file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Configuration> {
+        Configuration::from_slice(&read(path)?)
+    }
+
+    /// Writes configuration JSON in the same form as VRChat.
+    /// Unknown fields are written after known ones.
+    pub fn to_vec(&self) -> Result<Vec<u8>> {
+        let mut bytes = vec![];
+        let mut serializer = serde_json::Serializer::with_formatter(
+            &mut bytes,
+            PrettyFormatter::with_indent(INDENT),
+        );
+        self.serialize(&mut serializer)?;
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
+
+    /// Saves configuration file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        write(path, self.to_vec()?)?;
+        Ok(())
+    }
+
+    /// Returns the parameter.
+    pub fn parameter(&self, name: &str) -> Option<&Parameter> {
+        self.parameters.iter().find(|p| p.name == name)
+    }
+
+    /// Returns the parameter for editing.
+    pub fn parameter_mut(&mut self, name: &str) -> Option<&mut Parameter> {
+        self.parameters.iter_mut().find(|p| p.name == name)
+    }
+
+    /// Adds the parameter at the end.
+    /// If the parameter with the same name exists, it is replaced in place and returned.
+    pub fn add_parameter(&mut self, parameter: Parameter) -> Option<Parameter> {
+        match self.parameter_mut(&parameter.name) {
+            Some(existing) => Some(replace(existing, parameter)),
+            None => {
+                self.parameters.push(parameter);
+                None
+            }
+        }
+    }
+
+    /// Removes the parameter.
+    pub fn remove_parameter(&mut self, name: &str) -> Option<Parameter> {
+        let index = self.parameters.iter().position(|p| p.name == name)?;
+        Some(self.parameters.remove(index))
+    }
+
+    /// Removes input of the parameter so that it is not changed via OSC.
+    /// Returns `false` if the parameter does not exist.
+    pub fn disable_input(&mut self, name: &str) -> bool {
+        match self.parameter_mut(name) {
+            Some(parameter) => {
+                parameter.input = None;
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 /// Represents a parameter definition in configuration.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Parameter {
     pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub input: Option<ParameterAddress>,
+
     pub output: ParameterAddress,
+
+    /// Fields not known by this crate, kept as they are.
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
+}
+
+impl Parameter {
+    /// Creates a parameter with input and output at `/avatar/parameters/<name>`.
+    pub fn new(name: &str, parameter_type: ParameterDataType) -> Parameter {
+        let address = ParameterAddress::new(
+            &format!("{}{}", PARAMETER_ADDRESS_PREFIX, name),
+            parameter_type,
+        );
+        Parameter {
+            name: name.into(),
+            input: Some(address.clone()),
+            output: address,
+            unknown_fields: UnknownFields::default(),
+        }
+    }
+
+    /// Creates a parameter only with output at `/avatar/parameters/<name>`.
+    pub fn output_only(name: &str, parameter_type: ParameterDataType) -> Parameter {
+        Parameter {
+            input: None,
+            ..Parameter::new(name, parameter_type)
+        }
+    }
 }
 
 /// Represents a pair of parameter address and data type.
@@ -26,10 +159,26 @@ pub struct ParameterAddress {
     /// Parameter data type. Originally named `type`.
     #[serde(rename = "type")]
     pub parameter_type: ParameterDataType,
+
+    /// Fields not known by this crate, kept as they are.
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
+}
+
+impl ParameterAddress {
+    /// Creates a pair without unknown fields.
+    pub fn new(address: &str, parameter_type: ParameterDataType) -> ParameterAddress {
+        ParameterAddress {
+            address: address.into(),
+            parameter_type,
+            unknown_fields: UnknownFields::default(),
+        }
+    }
 }
 
 /// Describes the data type.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum ParameterDataType {
     /// Boolean parameter.
     Bool,
@@ -39,4 +188,123 @@ pub enum ParameterDataType {
 
     /// Float-point number parameter.
     Float,
+
+    /// Data type not known by this crate, such as one added by newer VRChat.
+    /// Original name is kept.
+    Other(String),
+}
+
+impl From<String> for ParameterDataType {
+    fn from(name: String) -> ParameterDataType {
+        match name.as_str() {
+            "Bool" => ParameterDataType::Bool,
+            "Int" => ParameterDataType::Int,
+            "Float" => ParameterDataType::Float,
+            _ => ParameterDataType::Other(name),
+        }
+    }
+}
+
+impl From<ParameterDataType> for String {
+    fn from(data_type: ParameterDataType) -> String {
+        match data_type {
+            ParameterDataType::Bool => "Bool".into(),
+            ParameterDataType::Int => "Int".into(),
+            ParameterDataType::Float => "Float".into(),
+            ParameterDataType::Other(name) => name,
+        }
+    }
+}
+
+/// JSON object fields which are not known by this crate.
+/// Their original order is kept, so that they are written back as they were.
+///
+/// They are compared, ordered and hashed regardless of the order,
+/// in serialized form with sorted keys since JSON values have no total order.
+#[derive(Debug, Clone, Default)]
+pub struct UnknownFields(pub Vec<(String, JsonValue)>);
+
+impl UnknownFields {
+    /// Whether no unknown field is present.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the value of the field.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Returns the fields as JSON map, whose keys are sorted.
+    fn to_map(&self) -> Map<String, JsonValue> {
+        self.0.iter().cloned().collect()
+    }
+
+    fn serialized(&self) -> String {
+        serde_json::to_string(&self.to_map()).expect("JSON map should be serialized")
+    }
+}
+
+impl PartialEq for UnknownFields {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_map() == other.to_map()
+    }
+}
+
+impl Eq for UnknownFields {}
+
+impl PartialOrd for UnknownFields {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for UnknownFields {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self == other {
+            return Ordering::Equal;
+        }
+        self.serialized().cmp(&other.serialized())
+    }
+}
+
+impl Hash for UnknownFields {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.serialized().hash(state);
+    }
+}
+
+impl Serialize for UnknownFields {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+    }
+}
+
+impl<'de> Deserialize<'de> for UnknownFields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> StdResult<Self, D::Error> {
+        deserializer.deserialize_map(UnknownFieldsVisitor)
+    }
+}
+
+/// Visitor collecting map entries in their original order.
+struct UnknownFieldsVisitor;
+
+impl<'de> Visitor<'de> for UnknownFieldsVisitor {
+    type Value = UnknownFields;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> StdResult<UnknownFields, A::Error> {
+        let mut fields: Vec<(String, JsonValue)> = vec![];
+        while let Some((key, value)) = access.next_entry::<String, JsonValue>()? {
+            // Duplicated keys keep the first position and the last value, as JSON maps do.
+            match fields.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, existing)) => *existing = value,
+                None => fields.push((key, value)),
+            }
+        }
+        Ok(UnknownFields(fields))
+    }
 }
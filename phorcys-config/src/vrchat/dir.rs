@@ -1,7 +1,28 @@
 //! Helpers for resolving VRChat's config file.
 
+use crate::{
+    error::Result,
+    vrchat::{config::Configuration, proton},
+};
+
+use std::{
+    env::var_os,
+    fs::{read_dir, remove_file},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
 use directories::BaseDirs;
 
+/// Environment variable which overrides OSC directory.
+pub const OSC_DIR_VARIABLE: &str = "VRCHAT_OSC_DIR";
+
+/// Prefix of user directories in OSC directory.
+const USER_PREFIX: &str = "usr_";
+
+/// Prefix of avatar configuration file names.
+const AVATAR_PREFIX: &str = "avtr_";
+
 pub struct VrcDirs(BaseDirs);
 
 impl VrcDirs {
@@ -13,11 +34,140 @@ impl VrcDirs {
 
     /// Returns OSC configuration directory of VRChat.
     /// e.g. `%PROFILE%\AppData\LocalLow\VRChat\VRChat\OSC`
-    pub fn osc_dir(&self) -> String {
-        self.0
-            .data_local_dir()
-            .join("../LocalLow/VRChat/VRChat/OSC")
-            .to_string_lossy()
-            .to_string()
+    ///
+    /// `VRCHAT_OSC_DIR` environment variable takes precedence if set.
+    /// On other than Windows, the directory in Proton prefix is searched from all Steam libraries,
+    /// and that of the default library is returned if not found.
+    pub fn osc_dir(&self) -> OscDir {
+        if let Some(path) = var_os(OSC_DIR_VARIABLE) {
+            return OscDir::new(path);
+        }
+
+        if cfg!(windows) {
+            OscDir::new(
+                self.0
+                    .data_local_dir()
+                    .join("../LocalLow/VRChat/VRChat/OSC"),
+            )
+        } else {
+            let (home_dir, data_dir) = (self.0.home_dir(), self.0.data_dir());
+            let path = proton::find_osc_dir(home_dir, data_dir)
+                .unwrap_or_else(|| proton::osc_dir_in_library(&data_dir.join("Steam")));
+            OscDir::new(path)
+        }
+    }
+}
+
+/// OSC configuration directory of VRChat.
+/// Avatar configurations are placed as `usr_*/Avatars/avtr_*.json`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OscDir(PathBuf);
+
+impl OscDir {
+    /// Wraps the path of OSC directory.
+    pub fn new(path: impl Into<PathBuf>) -> OscDir {
+        OscDir(path.into())
+    }
+
+    /// Returns the path of this directory.
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Enumerates user IDs such as `usr_00000000-0000-0000-0000-000000000000`, in sorted order.
+    pub fn user_ids(&self) -> Result<Vec<String>> {
+        let mut user_ids = vec![];
+        for entry in read_dir(&self.0)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            match entry.file_name().into_string() {
+                Ok(name) if name.starts_with(USER_PREFIX) => user_ids.push(name),
+                _ => (),
+            }
+        }
+
+        user_ids.sort();
+        Ok(user_ids)
+    }
+
+    /// Returns the directory of avatar configurations of the user.
+    pub fn avatars_dir(&self, user_id: &str) -> PathBuf {
+        self.0.join(user_id).join("Avatars")
     }
+
+    /// Returns the path of avatar configuration, whether it exists or not.
+    pub fn avatar_config_path(&self, user_id: &str, avatar_id: &str) -> PathBuf {
+        self.avatars_dir(user_id)
+            .join(format!("{}.json", avatar_id))
+    }
+
+    /// Lists avatar configuration files of the user, in sorted order.
+    /// Returns empty if the user has no `Avatars` directory.
+    pub fn avatar_config_paths(&self, user_id: &str) -> Result<Vec<PathBuf>> {
+        let avatars_dir = self.avatars_dir(user_id);
+        if !avatars_dir.is_dir() {
+            return Ok(vec![]);
+        }
+
+        let mut paths = vec![];
+        for entry in read_dir(avatars_dir)? {
+            let path = entry?.path();
+            if path.is_file() && is_avatar_config_name(&path) {
+                paths.push(path);
+            }
+        }
+
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Loads all avatar configurations of the user.
+    pub fn load_avatar_configs(&self, user_id: &str) -> Result<Vec<(PathBuf, Configuration)>> {
+        self.avatar_config_paths(user_id)?
+            .into_iter()
+            .map(|path| {
+                let configuration = Configuration::load(&path)?;
+                Ok((path, configuration))
+            })
+            .collect()
+    }
+
+    /// Removes the avatar configuration so that VRChat regenerates it on next load of the avatar.
+    /// Returns `false` if it does not exist.
+    pub fn reset_avatar_config(&self, user_id: &str, avatar_id: &str) -> Result<bool> {
+        match remove_file(self.avatar_config_path(user_id, avatar_id)) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Finds the configuration file of the avatar from all users.
+    /// When multiple users have it, the first one in order of `user_ids` is returned.
+    pub fn find_avatar_config(&self, avatar_id: &str) -> Result<Option<PathBuf>> {
+        for user_id in self.user_ids()? {
+            let path = self.avatar_config_path(&user_id, avatar_id);
+            if path.is_file() {
+                return Ok(Some(path));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl AsRef<Path> for OscDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+/// Whether the file name is of avatar configuration, like `avtr_*.json`.
+pub(crate) fn is_avatar_config_name(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "json")
+        && path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .is_some_and(|s| s.starts_with(AVATAR_PREFIX))
 }
@@ -1,2 +1,6 @@
-pub mod dir;
 pub mod config;
+pub mod dir;
+pub mod proton;
+pub mod state;
+pub mod value;
+pub mod watch;
@@ -0,0 +1,141 @@
+//! Resolution of VRChat directories running under Steam Proton.
+//!
+//! On Linux, VRChat stores its data in the Wine prefix of Proton, like
+//! `steamapps/compatdata/438100/pfx/drive_c/users/steamuser/AppData/LocalLow/VRChat/VRChat/OSC`.
+//! The prefix is placed in the Steam library VRChat is installed in,
+//! which may be other than the default one.
+
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+/// Steam application ID of VRChat.
+pub const VRCHAT_APP_ID: u32 = 438100;
+
+/// Returns candidates of Steam installation directory, in order of priority.
+/// Native, legacy symlink, and Flatpak installations are considered.
+pub fn steam_roots(home_dir: &Path, data_dir: &Path) -> Vec<PathBuf> {
+    vec![
+        data_dir.join("Steam"),
+        home_dir.join(".steam/steam"),
+        home_dir.join(".steam/root"),
+        home_dir.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+    ]
+}
+
+/// Returns Steam libraries of the installation, including itself.
+/// Additional libraries are read from `libraryfolders.vdf`.
+pub fn steam_libraries(steam_root: &Path) -> Vec<PathBuf> {
+    let mut libraries = vec![steam_root.to_path_buf()];
+    for vdf_path in [
+        steam_root.join("steamapps/libraryfolders.vdf"),
+        steam_root.join("config/libraryfolders.vdf"),
+    ] {
+        let vdf = match read_to_string(vdf_path) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        for library in library_folders(&vdf) {
+            if !libraries.contains(&library) {
+                libraries.push(library);
+            }
+        }
+    }
+    libraries
+}
+
+/// Returns VRChat OSC directory in the Proton prefix of the library, whether it exists or not.
+pub fn osc_dir_in_library(library: &Path) -> PathBuf {
+    library
+        .join("steamapps/compatdata")
+        .join(VRCHAT_APP_ID.to_string())
+        .join("pfx/drive_c/users/steamuser/AppData/LocalLow/VRChat/VRChat/OSC")
+}
+
+/// Finds existing VRChat OSC directory from all Steam libraries.
+pub fn find_osc_dir(home_dir: &Path, data_dir: &Path) -> Option<PathBuf> {
+    steam_roots(home_dir, data_dir)
+        .iter()
+        .filter(|r| r.is_dir())
+        .flat_map(|r| steam_libraries(r))
+        .map(|l| osc_dir_in_library(&l))
+        .find(|d| d.is_dir())
+}
+
+/// Extracts library paths from the content of `libraryfolders.vdf`.
+///
+/// Both of current format (`"path"` in each numbered section)
+/// and legacy format (numbered keys with path values) are supported.
+pub fn library_folders(vdf: &str) -> Vec<PathBuf> {
+    let tokens = tokenize_vdf(vdf);
+
+    let mut folders = vec![];
+    let mut depth = 0usize;
+    let mut rest = &tokens[..];
+    while let Some((token, next)) = rest.split_first() {
+        rest = next;
+        match token {
+            VdfToken::Open => depth += 1,
+            VdfToken::Close => depth = depth.saturating_sub(1),
+            VdfToken::String(key) => {
+                let value = match rest.first() {
+                    Some(VdfToken::String(v)) => v,
+                    _ => continue,
+                };
+                rest = &rest[1..];
+
+                let is_legacy_entry = depth == 1 && key.bytes().all(|b| b.is_ascii_digit());
+                if key == "path" || is_legacy_entry {
+                    folders.push(PathBuf::from(value));
+                }
+            }
+        }
+    }
+    folders
+}
+
+/// Token of Valve's KeyValues format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VdfToken {
+    String(String),
+    Open,
+    Close,
+}
+
+/// Splits VDF into quoted strings and braces. Comments are skipped.
+fn tokenize_vdf(vdf: &str) -> Vec<VdfToken> {
+    let mut tokens = vec![];
+    let mut chars = vdf.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => tokens.push(VdfToken::Open),
+            '}' => tokens.push(VdfToken::Close),
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                let mut string = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => string.push('\n'),
+                            Some('t') => string.push('\t'),
+                            Some(escaped) => string.push(escaped),
+                            None => break,
+                        },
+                        otherwise => string.push(otherwise),
+                    }
+                }
+                tokens.push(VdfToken::String(string));
+            }
+            _ => (),
+        }
+    }
+    tokens
+}
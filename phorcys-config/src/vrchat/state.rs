@@ -0,0 +1,134 @@
+//! Current values of avatar parameters.
+
+use crate::vrchat::{
+    config::{Configuration, Parameter},
+    value::{ParameterValue, ValueResult},
+};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Instant,
+};
+
+use phorcys_osc::packet::{Message, MessageBuilder};
+
+/// Change of a parameter value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterChange {
+    /// Parameter name.
+    pub name: String,
+
+    /// Previous value, `None` if not received before.
+    pub old: Option<ParameterValue>,
+
+    /// Current value.
+    pub new: ParameterValue,
+
+    /// When the change was ingested.
+    pub at: Instant,
+}
+
+/// Tracks current values of parameters from OSC messages sent by the avatar.
+#[derive(Debug, Clone)]
+pub struct ParameterState {
+    entries: BTreeMap<String, Entry>,
+
+    /// Parameter names by output address.
+    names: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    parameter: Parameter,
+    value: Option<ParameterValue>,
+    changed_at: Option<Instant>,
+}
+
+impl ParameterState {
+    /// Creates a state with all parameters of the configuration, whose values are unknown.
+    pub fn new(configuration: &Configuration) -> ParameterState {
+        let mut entries = BTreeMap::new();
+        let mut names = HashMap::new();
+        for parameter in &configuration.parameters {
+            names.insert(parameter.output.address.clone(), parameter.name.clone());
+            entries.insert(
+                parameter.name.clone(),
+                Entry {
+                    parameter: parameter.clone(),
+                    value: None,
+                    changed_at: None,
+                },
+            );
+        }
+
+        ParameterState { entries, names }
+    }
+
+    /// Ingests a message received from the avatar.
+    ///
+    /// The first argument is coerced into the output type of the parameter.
+    /// Returns `None` if the address is not of any parameter, or the value is not changed.
+    pub fn ingest(
+        &mut self,
+        message: &Message,
+        now: Instant,
+    ) -> ValueResult<Option<ParameterChange>> {
+        let name = match self.names.get(message.address().as_str()) {
+            Some(n) => n,
+            None => return Ok(None),
+        };
+        let entry = self.entries.get_mut(name).expect("Should be registered");
+        let argument = match message.arguments().first() {
+            Some(a) => a,
+            None => return Ok(None),
+        };
+
+        let value = ParameterValue::coerce(argument, &entry.parameter.output.parameter_type)?;
+        if entry.value == Some(value) {
+            return Ok(None);
+        }
+
+        let old = entry.value.replace(value);
+        entry.changed_at = Some(now);
+        Ok(Some(ParameterChange {
+            name: name.clone(),
+            old,
+            new: value,
+            at: now,
+        }))
+    }
+
+    /// Returns the current value of the parameter.
+    pub fn value(&self, name: &str) -> Option<ParameterValue> {
+        self.entries.get(name)?.value
+    }
+
+    /// Returns when the value of the parameter was changed last.
+    pub fn changed_at(&self, name: &str) -> Option<Instant> {
+        self.entries.get(name)?.changed_at
+    }
+
+    /// Iterates over parameters and their current values, in order of name.
+    pub fn iter(&self) -> impl Iterator<Item = (&Parameter, Option<ParameterValue>)> {
+        self.entries.values().map(|e| (&e.parameter, e.value))
+    }
+
+    /// Generates messages which restore current values to the avatar inputs.
+    ///
+    /// Parameters without input or known value are skipped,
+    /// as well as those whose value cannot be converted into the input type
+    /// or whose input address is not valid OSC address.
+    pub fn snapshot_messages(&self) -> Vec<Message> {
+        self.entries
+            .values()
+            .filter_map(|entry| {
+                let input = entry.parameter.input.as_ref()?;
+                let value = entry.value?;
+                let value = ParameterValue::coerce(&value.into(), &input.parameter_type).ok()?;
+
+                let builder = MessageBuilder::new(&input.address).ok()?;
+                Some(builder.push_argument(value.into()).build())
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,164 @@
+//! Typed values of avatar parameters.
+
+use crate::vrchat::config::ParameterDataType;
+
+use std::result::Result as StdResult;
+
+use phorcys_osc::data::Value;
+use thiserror::Error as ThisError;
+
+/// Range of `Float` parameters.
+const FLOAT_RANGE: (f64, f64) = (-1.0, 1.0);
+
+/// Represents an error of parameter values.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, ThisError)]
+pub enum ValueError {
+    /// `Int` parameter must be in 0 to 255.
+    #[error("Int parameter out of range: {0}")]
+    IntOutOfRange(i64),
+
+    /// `Float` parameter must be in -1.0 to 1.0.
+    #[error("Float parameter out of range: {0}")]
+    FloatOutOfRange(f64),
+
+    /// The value cannot be converted into the type.
+    #[error("Cannot convert {value} into {data_type:?} parameter")]
+    TypeMismatch {
+        data_type: ParameterDataType,
+        value: Value,
+    },
+
+    /// The value is not of any parameter type.
+    #[error("Unsupported value for parameter: {0}")]
+    Unsupported(Value),
+}
+
+/// Result type for parameter values.
+pub type ValueResult<T> = StdResult<T, ValueError>;
+
+/// Represents a value of avatar parameter.
+/// Values constructed with `int`, `float`, `coerce` or conversions are checked for the range,
+/// while variants constructed directly are not.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum ParameterValue {
+    /// Boolean parameter.
+    Bool(bool),
+
+    /// Integer parameter (unsigned 8bit).
+    Int(u8),
+
+    /// Float-point number parameter, in -1.0 to 1.0.
+    Float(f32),
+}
+
+impl ParameterValue {
+    /// Creates `Int` value, checking the range.
+    pub fn int(value: i64) -> ValueResult<ParameterValue> {
+        u8::try_from(value)
+            .map(ParameterValue::Int)
+            .map_err(|_| ValueError::IntOutOfRange(value))
+    }
+
+    /// Creates `Float` value, checking the range.
+    pub fn float(value: f64) -> ValueResult<ParameterValue> {
+        if value.is_nan() || value < FLOAT_RANGE.0 || value > FLOAT_RANGE.1 {
+            return Err(ValueError::FloatOutOfRange(value));
+        }
+        Ok(ParameterValue::Float(value as f32))
+    }
+
+    /// Returns the data type of this value.
+    pub fn data_type(self) -> ParameterDataType {
+        match self {
+            ParameterValue::Bool(_) => ParameterDataType::Bool,
+            ParameterValue::Int(_) => ParameterDataType::Int,
+            ParameterValue::Float(_) => ParameterDataType::Float,
+        }
+    }
+
+    /// Converts OSC value into the type as VRChat does for incoming parameters.
+    ///
+    /// * Into `Bool`, numbers are `true` if not zero.
+    /// * Into `Int`, booleans are 0 or 1, and floats are truncated toward zero.
+    /// * Into `Float`, booleans are 0.0 or 1.0, and integers are converted as is.
+    ///
+    /// Integers and floats of both widths are accepted. Converted values are checked for the range,
+    /// so floats out of range for `Int` are reported as `IntOutOfRange` with the truncated value.
+    /// `NaN` cannot be converted into `Int`, and nothing can be converted into `Other` types.
+    pub fn coerce(value: &Value, data_type: &ParameterDataType) -> ValueResult<ParameterValue> {
+        let number = match value {
+            Value::Boolean(b) => Number::Int(*b as i64),
+            Value::Int32(x) => Number::Int(*x as i64),
+            Value::Int64(x) => Number::Int(*x),
+            Value::Float32(x) => Number::Float(*x as f64),
+            Value::Float64(x) => Number::Float(*x),
+            _ => {
+                return Err(ValueError::TypeMismatch {
+                    data_type: data_type.clone(),
+                    value: value.clone(),
+                })
+            }
+        };
+
+        match (data_type, number) {
+            (ParameterDataType::Bool, Number::Int(x)) => Ok(ParameterValue::Bool(x != 0)),
+            (ParameterDataType::Bool, Number::Float(x)) => Ok(ParameterValue::Bool(x != 0.0)),
+            (ParameterDataType::Int, Number::Int(x)) => ParameterValue::int(x),
+            (ParameterDataType::Int, Number::Float(x)) if !x.is_nan() => {
+                ParameterValue::int(x.trunc() as i64)
+            }
+            (ParameterDataType::Float, Number::Int(x)) => ParameterValue::float(x as f64),
+            (ParameterDataType::Float, Number::Float(x)) => ParameterValue::float(x),
+            (ParameterDataType::Int, Number::Float(_)) | (ParameterDataType::Other(_), _) => {
+                Err(ValueError::TypeMismatch {
+                    data_type: data_type.clone(),
+                    value: value.clone(),
+                })
+            }
+        }
+    }
+}
+
+/// Intermediate number in coercion.
+#[derive(Clone, Copy)]
+enum Number {
+    Int(i64),
+    Float(f64),
+}
+
+impl From<bool> for ParameterValue {
+    fn from(b: bool) -> Self {
+        ParameterValue::Bool(b)
+    }
+}
+
+impl From<u8> for ParameterValue {
+    fn from(x: u8) -> Self {
+        ParameterValue::Int(x)
+    }
+}
+
+impl From<ParameterValue> for Value {
+    fn from(value: ParameterValue) -> Self {
+        match value {
+            ParameterValue::Bool(b) => Value::Boolean(b),
+            ParameterValue::Int(x) => Value::Int32(x as i32),
+            ParameterValue::Float(x) => Value::Float32(x),
+        }
+    }
+}
+
+impl TryFrom<&Value> for ParameterValue {
+    type Error = ValueError;
+
+    /// Converts OSC value of the exact type, as VRChat sends.
+    fn try_from(value: &Value) -> ValueResult<ParameterValue> {
+        match value {
+            Value::Boolean(b) => Ok(ParameterValue::Bool(*b)),
+            Value::Int32(x) => ParameterValue::int(*x as i64),
+            Value::Float32(x) => ParameterValue::float(*x as f64),
+            otherwise => Err(ValueError::Unsupported(otherwise.clone())),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Watching changes of avatar configurations.
+
+use crate::{
+    error::{Error, Result},
+    vrchat::{
+        config::Configuration,
+        dir::{is_avatar_config_name, OscDir},
+    },
+};
+
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, DebouncedEvent, Debouncer};
+
+/// Change of an avatar configuration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigEvent {
+    /// New configuration file appeared.
+    Added {
+        path: PathBuf,
+        configuration: Configuration,
+    },
+
+    /// Configuration file was rewritten with different content.
+    Modified {
+        path: PathBuf,
+        configuration: Configuration,
+    },
+
+    /// Configuration file was removed.
+    Removed { path: PathBuf },
+}
+
+/// Watches OSC directory and reports changes of avatar configurations.
+///
+/// File system events are debounced by `delay`, so that files being written are not read.
+/// Rewriting with the same content is not reported.
+pub struct ConfigWatcher {
+    osc_dir: OscDir,
+    configurations: HashMap<PathBuf, Configuration>,
+    pending: VecDeque<Result<ConfigEvent>>,
+    receiver: Receiver<DebounceEventResult>,
+
+    // Stops watching when dropped.
+    _debouncer: Debouncer<RecommendedWatcher>,
+}
+
+impl ConfigWatcher {
+    /// Starts watching the OSC directory, which must exist.
+    /// Existing configurations are loaded but not reported.
+    pub fn new(osc_dir: OscDir, delay: Duration) -> Result<ConfigWatcher> {
+        let (sender, receiver) = channel();
+        let mut debouncer = new_debouncer(delay, sender)?;
+        debouncer
+            .watcher()
+            .watch(osc_dir.path(), RecursiveMode::Recursive)?;
+
+        let mut config_watcher = ConfigWatcher {
+            osc_dir,
+            configurations: HashMap::new(),
+            pending: VecDeque::new(),
+            receiver,
+            _debouncer: debouncer,
+        };
+        config_watcher.rescan()?;
+        config_watcher.pending.clear();
+        Ok(config_watcher)
+    }
+
+    /// Iterates over currently known configurations.
+    pub fn configurations(&self) -> impl Iterator<Item = (&Path, &Configuration)> {
+        self.configurations.iter().map(|(p, c)| (p.as_path(), c))
+    }
+
+    /// Waits for the next event until `timeout` elapses.
+    /// Returns `None` on timeout.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Option<Result<ConfigEvent>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+
+            // File system events not changing configurations do not extend the timeout.
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.receiver.recv_timeout(remaining) {
+                Ok(event) => self.handle(event),
+                Err(RecvTimeoutError::Timeout) => return None,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Some(Err(Error::Watch(notify::Error::generic(
+                        "Watcher disconnected",
+                    ))))
+                }
+            }
+        }
+    }
+
+    /// Converts debounced file system events into `ConfigEvent`s.
+    /// Debounced events only tell changed paths, so whether they still exist is checked.
+    fn handle(&mut self, result: DebounceEventResult) {
+        match result {
+            Ok(events) => {
+                for DebouncedEvent { path, .. } in events {
+                    if path.exists() {
+                        self.reload(path);
+                    } else {
+                        self.remove(path);
+                    }
+                }
+            }
+            Err(e) => {
+                // Events may have been lost, so configurations are compared with files.
+                self.pending.push_back(Err(e.into()));
+                if let Err(e) = self.rescan() {
+                    self.pending.push_back(Err(e));
+                }
+            }
+        }
+    }
+
+    /// Reads the file and reports if changed.
+    fn reload(&mut self, path: PathBuf) {
+        if !is_watched(&path) {
+            return;
+        }
+
+        let configuration = match Configuration::load(&path) {
+            Ok(c) => c,
+            Err(e) => {
+                self.pending.push_back(Err(e));
+                return;
+            }
+        };
+        let event = match self.configurations.get(&path) {
+            Some(known) if known == &configuration => return,
+            Some(_) => ConfigEvent::Modified {
+                path: path.clone(),
+                configuration: configuration.clone(),
+            },
+            None => ConfigEvent::Added {
+                path: path.clone(),
+                configuration: configuration.clone(),
+            },
+        };
+        self.configurations.insert(path, configuration);
+        self.pending.push_back(Ok(event));
+    }
+
+    /// Forgets the file and reports if it was known.
+    fn remove(&mut self, path: PathBuf) {
+        if self.configurations.remove(&path).is_some() {
+            self.pending.push_back(Ok(ConfigEvent::Removed { path }));
+        }
+    }
+
+    /// Reads all configurations and reports differences from known ones.
+    fn rescan(&mut self) -> Result<()> {
+        let mut paths = vec![];
+        for user_id in self.osc_dir.user_ids()? {
+            paths.extend(self.osc_dir.avatar_config_paths(&user_id)?);
+        }
+
+        let removed: Vec<_> = self
+            .configurations
+            .keys()
+            .filter(|p| !paths.contains(p))
+            .cloned()
+            .collect();
+        for path in removed {
+            self.remove(path);
+        }
+        for path in paths {
+            self.reload(path);
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for ConfigWatcher {
+    type Item = Result<ConfigEvent>;
+
+    /// Blocks until the next event.
+    /// Returns `None` when the underlying watcher stopped.
+    fn next(&mut self) -> Option<Result<ConfigEvent>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+
+            let event = self.receiver.recv().ok()?;
+            self.handle(event);
+        }
+    }
+}
+
+/// Whether the path is of avatar configuration, like `usr_*/Avatars/avtr_*.json`.
+fn is_watched(path: &Path) -> bool {
+    is_avatar_config_name(path)
+        && path
+            .parent()
+            .and_then(|p| p.file_name())
+            .is_some_and(|n| n == "Avatars")
+}
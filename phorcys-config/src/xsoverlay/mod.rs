@@ -107,7 +107,7 @@ impl NotificationBuilder {
 
     /// Sets opacity.
     pub fn opacity(mut self, opacity: f32) -> NotificationBuilder {
-        self.opacity = Some(opacity.max(0.0).min(1.0));
+        self.opacity = Some(opacity.clamp(0.0, 1.0));
         self
     }
 
@@ -20,11 +20,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/VRCLFeatureToggle".into(),
                 parameter_type: ParameterDataType::Int,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/VRCLFeatureToggle".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -34,11 +37,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/VRCFaceBlendV".into(),
                 parameter_type: ParameterDataType::Float,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/VRCFaceBlendV".into(),
                 parameter_type: ParameterDataType::Float,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -48,11 +54,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/VRCFaceBlendH".into(),
                 parameter_type: ParameterDataType::Float,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/VRCFaceBlendH".into(),
                 parameter_type: ParameterDataType::Float,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -62,11 +71,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Onegai".into(),
                 parameter_type: ParameterDataType::Bool,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Onegai".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -76,11 +88,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Boobs".into(),
                 parameter_type: ParameterDataType::Float,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Boobs".into(),
                 parameter_type: ParameterDataType::Float,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -90,11 +105,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Face Shocked".into(),
                 parameter_type: ParameterDataType::Bool,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Face Shocked".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -104,11 +122,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Face Spirit".into(),
                 parameter_type: ParameterDataType::Bool,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Face Spirit".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -118,11 +139,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Face Blue".into(),
                 parameter_type: ParameterDataType::Bool,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Face Blue".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -132,11 +156,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Face Tears".into(),
                 parameter_type: ParameterDataType::Bool,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Face Tears".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -146,11 +173,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Face Shy2".into(),
                 parameter_type: ParameterDataType::Bool,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Face Shy2".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -160,11 +190,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Face Shy1".into(),
                 parameter_type: ParameterDataType::Bool,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Face Shy1".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -174,11 +207,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Face Cheeks".into(),
                 parameter_type: ParameterDataType::Bool,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Face Cheeks".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -188,11 +224,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Mouth".into(),
                 parameter_type: ParameterDataType::Int,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Mouth".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -202,11 +241,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Pupils".into(),
                 parameter_type: ParameterDataType::Int,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Pupils".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -216,11 +258,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Eyelids".into(),
                 parameter_type: ParameterDataType::Int,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Eyelids".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -230,11 +275,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Eyebrows".into(),
                 parameter_type: ParameterDataType::Int,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Eyebrows".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -244,11 +292,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Item RickRoll".into(),
                 parameter_type: ParameterDataType::Bool,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Item RickRoll".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -258,11 +309,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Item Right".into(),
                 parameter_type: ParameterDataType::Int,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Item Right".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -272,11 +326,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Clothes NoBag".into(),
                 parameter_type: ParameterDataType::Bool,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Clothes NoBag".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -286,11 +343,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Clothes Legs".into(),
                 parameter_type: ParameterDataType::Int,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Clothes Legs".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -300,11 +360,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/Clothes Base".into(),
                 parameter_type: ParameterDataType::Int,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/Clothes Base".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -314,11 +377,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/System HandAnimated".into(),
                 parameter_type: ParameterDataType::Bool,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/System HandAnimated".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -328,11 +394,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/System FaceMirror".into(),
                 parameter_type: ParameterDataType::Bool,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/System FaceMirror".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -342,11 +411,14 @@ fn test_config_structure() {
             input: Some(ParameterAddress {
                 address: "/avatar/parameters/VRCEmote".into(),
                 parameter_type: ParameterDataType::Int,
+                unknown_fields: Default::default(),
             }),
             output: ParameterAddress {
                 address: "/avatar/parameters/VRCEmote".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -357,7 +429,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/VelocityZ".into(),
                 parameter_type: ParameterDataType::Float,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -368,7 +442,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/VelocityY".into(),
                 parameter_type: ParameterDataType::Float,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -379,7 +455,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/VelocityX".into(),
                 parameter_type: ParameterDataType::Float,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -390,7 +468,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/InStation".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -401,7 +481,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/Seated".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -412,7 +494,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/AFK".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -423,7 +507,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/Upright".into(),
                 parameter_type: ParameterDataType::Float,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -434,7 +520,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/AngularY".into(),
                 parameter_type: ParameterDataType::Float,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -445,7 +533,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/Grounded".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -456,7 +546,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/MuteSelf".into(),
                 parameter_type: ParameterDataType::Bool,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -467,7 +559,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/VRMode".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -478,7 +572,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/TrackingType".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -489,7 +585,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/GestureRightWeight".into(),
                 parameter_type: ParameterDataType::Float,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -500,7 +598,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/GestureRight".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -511,7 +611,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/GestureLeftWeight".into(),
                 parameter_type: ParameterDataType::Float,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -522,7 +624,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/GestureLeft".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -533,7 +637,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/Voice".into(),
                 parameter_type: ParameterDataType::Float,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
     assert_eq!(
@@ -544,7 +650,9 @@ fn test_config_structure() {
             output: ParameterAddress {
                 address: "/avatar/parameters/Viseme".into(),
                 parameter_type: ParameterDataType::Int,
-            }
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
         }
     );
 }
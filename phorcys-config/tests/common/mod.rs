@@ -0,0 +1,32 @@
+//! Helpers shared by integration tests.
+
+use std::{
+    env::temp_dir,
+    fs::{create_dir_all, remove_dir_all},
+    path::{Path, PathBuf},
+    process,
+};
+
+/// Empty temporary directory, which is removed when dropped even if the test panics.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates a directory unique to `name` and this process.
+    pub fn new(name: &str) -> TempDir {
+        let path = temp_dir().join(format!("phorcys-config-{}-{}", name, process::id()));
+        let _ = remove_dir_all(&path);
+        create_dir_all(&path).expect("Should create");
+        TempDir(path)
+    }
+
+    /// Returns the path of this directory.
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.0);
+    }
+}
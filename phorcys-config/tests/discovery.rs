@@ -0,0 +1,76 @@
+//! Tests the discovery of avatar configurations.
+
+mod common;
+
+use crate::common::TempDir;
+
+use phorcys_config::vrchat::{config::Configuration, dir::OscDir};
+
+use std::fs::{create_dir_all, write};
+
+const AVATAR_ID: &str = "avtr_00000000-0000-0000-0000-000000000000";
+
+/// Creates OSC directory with two users.
+fn create_osc_dir() -> TempDir {
+    let temp = TempDir::new("discovery");
+    let root = temp.path();
+
+    let source = include_str!("./parameters.json");
+    let avatars = root.join("usr_b/Avatars");
+    create_dir_all(&avatars).expect("Should create");
+    write(avatars.join(format!("{}.json", AVATAR_ID)), source).expect("Should write");
+
+    // Written by VRChat with BOM.
+    let mut bom_source = b"\xEF\xBB\xBF".to_vec();
+    bom_source.extend_from_slice(source.as_bytes());
+    write(avatars.join("avtr_ffffffff.json"), bom_source).expect("Should write");
+    write(avatars.join("avtr_ignored.txt"), "").expect("Should write");
+
+    create_dir_all(root.join("usr_a")).expect("Should create");
+    create_dir_all(root.join("other")).expect("Should create");
+    temp
+}
+
+#[test]
+fn test_discovery() {
+    let temp = create_osc_dir();
+    let root = temp.path();
+    let osc_dir = OscDir::new(root);
+
+    assert_eq!(
+        osc_dir.user_ids().expect("Should read"),
+        vec!["usr_a".to_string(), "usr_b".to_string()]
+    );
+    assert!(osc_dir
+        .avatar_config_paths("usr_a")
+        .expect("Should read")
+        .is_empty());
+    assert_eq!(
+        osc_dir.avatar_config_paths("usr_b").expect("Should read"),
+        vec![
+            root.join(format!("usr_b/Avatars/{}.json", AVATAR_ID)),
+            root.join("usr_b/Avatars/avtr_ffffffff.json"),
+        ]
+    );
+
+    let configurations = osc_dir.load_avatar_configs("usr_b").expect("Should load");
+    assert_eq!(configurations.len(), 2);
+    assert_eq!(configurations[0].1, configurations[1].1);
+    assert_eq!(configurations[1].1.id, AVATAR_ID);
+
+    let found = osc_dir
+        .find_avatar_config(AVATAR_ID)
+        .expect("Should read")
+        .expect("Should be found");
+    assert_eq!(found, osc_dir.avatar_config_path("usr_b", AVATAR_ID));
+    assert_eq!(
+        Configuration::load(&found).expect("Should load").name,
+        "セフィラちゃん（夏稀風）"
+    );
+    assert_eq!(
+        osc_dir
+            .find_avatar_config("avtr_unknown")
+            .expect("Should read"),
+        None
+    );
+}
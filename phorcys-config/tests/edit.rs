@@ -0,0 +1,135 @@
+//! Tests editing and writing avatar configurations.
+
+mod common;
+
+use crate::common::TempDir;
+
+use phorcys_config::vrchat::{
+    config::{Configuration, Parameter, ParameterDataType},
+    dir::OscDir,
+};
+
+use std::{
+    cmp::Ordering,
+    collections::hash_map::DefaultHasher,
+    fs::{create_dir_all, read},
+    hash::{Hash, Hasher},
+};
+
+#[test]
+fn test_write_same_form() {
+    let source = include_str!("./parameters.json");
+    let config = Configuration::from_slice(source.as_bytes()).expect("Invalid form");
+    assert_eq!(
+        String::from_utf8(config.to_vec().expect("Should write")).expect("Should be UTF-8"),
+        source
+    );
+}
+
+#[test]
+fn test_unknown_fields() {
+    let source = r#"{
+    "id": "avtr_0",
+    "name": "Avatar",
+    "parameters": [
+        {
+            "name": "Foo",
+            "output": {
+                "address": "/avatar/parameters/Foo",
+                "type": "Bool",
+                "note": "kept",
+                "defaultValue": false
+            },
+            "hidden": true
+        }
+    ],
+    "version": 2,
+    "author": "someone"
+}
+"#;
+    let mut bom_source = b"\xEF\xBB\xBF".to_vec();
+    bom_source.extend_from_slice(source.as_bytes());
+
+    let config = Configuration::from_slice(&bom_source).expect("Invalid form");
+    assert_eq!(config.unknown_fields.get("version"), Some(&2.into()));
+    assert_eq!(
+        config
+            .unknown_fields
+            .0
+            .iter()
+            .map(|(k, _)| k)
+            .collect::<Vec<_>>(),
+        vec!["version", "author"]
+    );
+    assert_eq!(
+        config.parameters[0].unknown_fields.get("hidden"),
+        Some(&true.into())
+    );
+    assert!(config.parameters[0].input.is_none());
+    assert_eq!(
+        String::from_utf8(config.to_vec().expect("Should write")).expect("Should be UTF-8"),
+        source
+    );
+
+    // Order of unknown fields does not affect equality and hash.
+    let mut reordered = config.clone();
+    reordered.unknown_fields.0.reverse();
+    assert_eq!(reordered, config);
+    assert_eq!(hash(&reordered), hash(&config));
+    assert_eq!(reordered.cmp(&config), Ordering::Equal);
+}
+
+fn hash(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_edit() {
+    let source = include_str!("./parameters.json");
+    let mut config = Configuration::from_slice(source.as_bytes()).expect("Invalid form");
+
+    assert!(config.disable_input("Mouth"));
+    assert!(!config.disable_input("Unknown"));
+    assert_eq!(
+        config.parameter("Mouth").and_then(|p| p.input.as_ref()),
+        None
+    );
+
+    let removed = config.remove_parameter("Viseme").expect("Should exist");
+    assert_eq!(removed.output.parameter_type, ParameterDataType::Int);
+    assert!(config.remove_parameter("Viseme").is_none());
+
+    let added = Parameter::new("Added", ParameterDataType::Float);
+    assert_eq!(added.output.address, "/avatar/parameters/Added");
+    assert_eq!(added.input.as_ref(), Some(&added.output));
+    assert_eq!(config.add_parameter(added), None);
+    let replaced = config.add_parameter(Parameter::output_only("Added", ParameterDataType::Bool));
+    assert_eq!(
+        replaced.map(|p| p.output.parameter_type),
+        Some(ParameterDataType::Float)
+    );
+    assert_eq!(config.parameters.len(), 42);
+    assert_eq!(config.parameters[41].name, "Added");
+
+    let temp = TempDir::new("edit");
+    let osc_dir = OscDir::new(temp.path());
+    let path = osc_dir.avatar_config_path("usr_a", &config.id);
+    create_dir_all(path.parent().expect("Should have parent")).expect("Should create");
+
+    config.save(&path).expect("Should save");
+    assert_eq!(Configuration::load(&path).expect("Should load"), config);
+    assert_eq!(
+        read(&path).expect("Should read"),
+        config.to_vec().expect("Should write")
+    );
+
+    assert!(osc_dir
+        .reset_avatar_config("usr_a", &config.id)
+        .expect("Should remove"));
+    assert!(!path.exists());
+    assert!(!osc_dir
+        .reset_avatar_config("usr_a", &config.id)
+        .expect("Should remove"));
+}
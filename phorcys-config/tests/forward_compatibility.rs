@@ -0,0 +1,108 @@
+//! Tests the acceptance of configurations written by newer VRChat.
+
+use phorcys_config::vrchat::{
+    config::{Configuration, Parameter, ParameterAddress, ParameterDataType, UnknownFields},
+    state::ParameterState,
+};
+use serde_json::{json, Value as JsonValue};
+
+const SOURCE: &[u8] = include_bytes!("./parameters_future.json");
+
+fn unknown_fields(fields: JsonValue) -> UnknownFields {
+    match fields {
+        JsonValue::Object(map) => UnknownFields(map.into_iter().collect()),
+        _ => UnknownFields::default(),
+    }
+}
+
+#[test]
+fn test_future_config_structure() {
+    let config = Configuration::from_slice(SOURCE).expect("Invalid form");
+
+    assert_eq!(config.id, "avtr_00000000-0000-0000-0000-000000000001");
+    assert_eq!(config.name, "Future Avatar");
+    assert_eq!(
+        config.unknown_fields,
+        unknown_fields(json!({ "schemaVersion": 2 }))
+    );
+    assert_eq!(config.parameters.len(), 2);
+    assert_eq!(
+        config.parameters[0],
+        Parameter {
+            name: "Mouth".into(),
+            input: Some(ParameterAddress {
+                address: "/avatar/parameters/Mouth".into(),
+                parameter_type: ParameterDataType::Int,
+                unknown_fields: Default::default(),
+            }),
+            output: ParameterAddress {
+                address: "/avatar/parameters/Mouth".into(),
+                parameter_type: ParameterDataType::Int,
+                unknown_fields: Default::default(),
+            },
+            unknown_fields: Default::default(),
+        }
+    );
+    assert_eq!(
+        config.parameters[1],
+        Parameter {
+            name: "Position".into(),
+            input: Some(ParameterAddress {
+                address: "/avatar/parameters/Position".into(),
+                parameter_type: ParameterDataType::Other("Vector3".into()),
+                unknown_fields: unknown_fields(json!({ "dimension": 3 })),
+            }),
+            output: ParameterAddress {
+                address: "/avatar/parameters/Position".into(),
+                parameter_type: ParameterDataType::Other("Vector3".into()),
+                unknown_fields: unknown_fields(json!({ "dimension": 3 })),
+            },
+            unknown_fields: unknown_fields(json!({ "synced": true })),
+        }
+    );
+}
+
+#[test]
+fn test_future_config_round_trip() {
+    let config = Configuration::from_slice(SOURCE).expect("Invalid form");
+    let written = config.to_vec().expect("Should write");
+
+    // The BOM is not written back, but everything else is kept as it was.
+    assert_eq!(
+        written,
+        SOURCE
+            .strip_prefix(b"\xEF\xBB\xBF")
+            .expect("Should have BOM")
+    );
+    assert_eq!(
+        Configuration::from_slice(&written).expect("Should read"),
+        config
+    );
+}
+
+#[test]
+fn test_known_types_serialized() {
+    for (name, data_type) in [
+        ("Bool", ParameterDataType::Bool),
+        ("Int", ParameterDataType::Int),
+        ("Float", ParameterDataType::Float),
+        ("Vector3", ParameterDataType::Other("Vector3".into())),
+    ] {
+        assert_eq!(
+            serde_json::to_value(&data_type).expect("Should serialize"),
+            json!(name)
+        );
+        assert_eq!(
+            serde_json::from_value::<ParameterDataType>(json!(name)).expect("Should deserialize"),
+            data_type
+        );
+    }
+}
+
+#[test]
+fn test_future_config_state() {
+    let config = Configuration::from_slice(SOURCE).expect("Invalid form");
+    let state = ParameterState::new(&config);
+    assert_eq!(state.iter().count(), 2);
+    assert!(state.snapshot_messages().is_empty());
+}
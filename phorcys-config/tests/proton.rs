@@ -0,0 +1,78 @@
+//! Tests the resolution of VRChat directories under Steam Proton.
+
+mod common;
+
+use crate::common::TempDir;
+
+use phorcys_config::vrchat::proton::{find_osc_dir, library_folders, osc_dir_in_library};
+
+use std::{
+    fs::{create_dir_all, write},
+    path::PathBuf,
+};
+
+#[test]
+fn test_library_folders() {
+    let current = r#"
+"libraryfolders"
+{
+    "0"
+    {
+        "path"      "/home/user/.local/share/Steam"
+        "label"     ""
+        "apps"
+        {
+            "438100"    "123456789"
+        }
+    }
+    "1"
+    {
+        "path"      "/mnt/games/Steam Library"
+    }
+}
+"#;
+    assert_eq!(
+        library_folders(current),
+        vec![
+            PathBuf::from("/home/user/.local/share/Steam"),
+            PathBuf::from("/mnt/games/Steam Library"),
+        ]
+    );
+
+    let legacy = r#"
+"LibraryFolders"
+{
+    // Comment
+    "TimeNextStatsReport"   "1600000000"
+    "ContentStatsID"        "-1234"
+    "1"     "D:\\SteamLibrary"
+}
+"#;
+    assert_eq!(
+        library_folders(legacy),
+        vec![PathBuf::from(r"D:\SteamLibrary")]
+    );
+}
+
+#[test]
+fn test_find_osc_dir() {
+    let temp = TempDir::new("proton");
+    let root = temp.path();
+
+    let home_dir = root.join("home");
+    let data_dir = home_dir.join(".local/share");
+    let steam_root = data_dir.join("Steam");
+    let library = root.join("library");
+    create_dir_all(steam_root.join("steamapps")).expect("Should create");
+    assert_eq!(find_osc_dir(&home_dir, &data_dir), None);
+
+    let vdf = format!(
+        "\"libraryfolders\"\n{{\n\"0\"\n{{\n\"path\" \"{}\"\n}}\n\"1\"\n{{\n\"path\" \"{}\"\n}}\n}}\n",
+        steam_root.display(),
+        library.display()
+    );
+    write(steam_root.join("steamapps/libraryfolders.vdf"), vdf).expect("Should write");
+    let osc_dir = osc_dir_in_library(&library);
+    create_dir_all(&osc_dir).expect("Should create");
+    assert_eq!(find_osc_dir(&home_dir, &data_dir), Some(osc_dir));
+}
@@ -0,0 +1,80 @@
+//! Tests tracking parameter values.
+
+use phorcys_config::vrchat::{
+    config::Configuration,
+    state::{ParameterChange, ParameterState},
+    value::{ParameterValue, ValueError},
+};
+use phorcys_osc::{data::Value, packet::MessageBuilder};
+
+use std::time::{Duration, Instant};
+
+fn message(address: &str, value: Value) -> phorcys_osc::packet::Message {
+    MessageBuilder::new(address)
+        .expect("Should valid")
+        .push_argument(value)
+        .build()
+}
+
+#[test]
+fn test_parameter_state() {
+    let configuration: Configuration =
+        serde_json::from_str(include_str!("./parameters.json")).expect("Invalid form");
+    let mut state = ParameterState::new(&configuration);
+    let start = Instant::now();
+    assert_eq!(state.iter().count(), 42);
+    assert_eq!(state.value("Mouth"), None);
+
+    let change = state
+        .ingest(&message("/avatar/parameters/Mouth", Value::Int32(3)), start)
+        .expect("Should valid");
+    assert_eq!(
+        change,
+        Some(ParameterChange {
+            name: "Mouth".into(),
+            old: None,
+            new: ParameterValue::Int(3),
+            at: start,
+        })
+    );
+
+    // Unchanged values and unknown addresses are not reported.
+    let later = start + Duration::from_secs(1);
+    let unchanged = message("/avatar/parameters/Mouth", Value::Int32(3));
+    assert_eq!(state.ingest(&unchanged, later), Ok(None));
+    assert_eq!(state.changed_at("Mouth"), Some(start));
+    let unknown = message("/avatar/parameters/Unknown", Value::Int32(3));
+    assert_eq!(state.ingest(&unknown, later), Ok(None));
+
+    let change = state
+        .ingest(&message("/avatar/parameters/Mouth", Value::Int32(5)), later)
+        .expect("Should valid")
+        .expect("Should change");
+    assert_eq!(change.old, Some(ParameterValue::Int(3)));
+    assert_eq!(change.new, ParameterValue::Int(5));
+    assert_eq!(state.changed_at("Mouth"), Some(later));
+
+    let out_of_range = message("/avatar/parameters/Mouth", Value::Int32(256));
+    assert_eq!(
+        state.ingest(&out_of_range, later),
+        Err(ValueError::IntOutOfRange(256))
+    );
+    assert_eq!(state.value("Mouth"), Some(ParameterValue::Int(5)));
+
+    // Output-only parameters are not included in snapshot.
+    let grounded = message("/avatar/parameters/Grounded", Value::Boolean(true));
+    state.ingest(&grounded, later).expect("Should valid");
+    state
+        .ingest(
+            &message("/avatar/parameters/VRCFaceBlendV", Value::Float32(0.5)),
+            later,
+        )
+        .expect("Should valid");
+    assert_eq!(
+        state.snapshot_messages(),
+        vec![
+            message("/avatar/parameters/Mouth", Value::Int32(5)),
+            message("/avatar/parameters/VRCFaceBlendV", Value::Float32(0.5)),
+        ]
+    );
+}
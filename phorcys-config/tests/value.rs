@@ -0,0 +1,106 @@
+//! Tests the validation and conversion of parameter values.
+
+use phorcys_config::vrchat::{
+    config::ParameterDataType,
+    value::{ParameterValue, ValueError},
+};
+use phorcys_osc::data::Value;
+
+#[test]
+fn test_validated_constructors() {
+    assert_eq!(ParameterValue::int(255), Ok(ParameterValue::Int(255)));
+    assert_eq!(
+        ParameterValue::int(256),
+        Err(ValueError::IntOutOfRange(256))
+    );
+    assert_eq!(ParameterValue::int(-1), Err(ValueError::IntOutOfRange(-1)));
+
+    assert_eq!(ParameterValue::float(-1.0), Ok(ParameterValue::Float(-1.0)));
+    assert_eq!(
+        ParameterValue::float(1.5),
+        Err(ValueError::FloatOutOfRange(1.5))
+    );
+    assert!(ParameterValue::float(f64::NAN).is_err());
+}
+
+#[test]
+fn test_osc_conversion() {
+    assert_eq!(
+        ParameterValue::try_from(&Value::Int32(3)),
+        Ok(ParameterValue::Int(3))
+    );
+    assert_eq!(
+        ParameterValue::try_from(&Value::Float32(2.0)),
+        Err(ValueError::FloatOutOfRange(2.0))
+    );
+    assert_eq!(
+        ParameterValue::try_from(&Value::Int64(3)),
+        Err(ValueError::Unsupported(Value::Int64(3)))
+    );
+
+    assert_eq!(Value::from(ParameterValue::Int(3)), Value::Int32(3));
+    assert_eq!(
+        Value::from(ParameterValue::Bool(true)),
+        Value::Boolean(true)
+    );
+    assert_eq!(Value::from(ParameterValue::Float(0.5)), Value::Float32(0.5));
+}
+
+#[test]
+fn test_coercion() {
+    use ParameterDataType::{Bool, Float, Int, Other};
+
+    let cases = [
+        (Value::Int32(2), Bool, Ok(ParameterValue::Bool(true))),
+        (Value::Float32(0.0), Bool, Ok(ParameterValue::Bool(false))),
+        (Value::Boolean(true), Int, Ok(ParameterValue::Int(1))),
+        (Value::Int64(200), Int, Ok(ParameterValue::Int(200))),
+        (Value::Float64(3.7), Int, Ok(ParameterValue::Int(3))),
+        (Value::Int32(300), Int, Err(ValueError::IntOutOfRange(300))),
+        (
+            Value::Float32(-1.5),
+            Int,
+            Err(ValueError::IntOutOfRange(-1)),
+        ),
+        (
+            Value::Float64(f64::INFINITY),
+            Int,
+            Err(ValueError::IntOutOfRange(i64::MAX)),
+        ),
+        (Value::Boolean(true), Float, Ok(ParameterValue::Float(1.0))),
+        (Value::Int32(0), Float, Ok(ParameterValue::Float(0.0))),
+        (
+            Value::Int32(2),
+            Float,
+            Err(ValueError::FloatOutOfRange(2.0)),
+        ),
+        (
+            Value::Float64(-0.25),
+            Float,
+            Ok(ParameterValue::Float(-0.25)),
+        ),
+        (
+            Value::String("1".into()),
+            Float,
+            Err(ValueError::TypeMismatch {
+                data_type: Float,
+                value: Value::String("1".into()),
+            }),
+        ),
+        (
+            Value::Float32(0.5),
+            Other("Vector3".into()),
+            Err(ValueError::TypeMismatch {
+                data_type: Other("Vector3".into()),
+                value: Value::Float32(0.5),
+            }),
+        ),
+    ];
+    for (value, data_type, expected) in cases {
+        assert_eq!(ParameterValue::coerce(&value, &data_type), expected);
+    }
+    assert!(matches!(
+        ParameterValue::coerce(&Value::Float32(f32::NAN), &Int),
+        Err(ValueError::TypeMismatch { data_type: Int, .. })
+    ));
+}
@@ -0,0 +1,113 @@
+//! Tests watching avatar configurations.
+
+mod common;
+
+use crate::common::TempDir;
+
+use phorcys_config::vrchat::{
+    config::Configuration,
+    dir::OscDir,
+    watch::{ConfigEvent, ConfigWatcher},
+};
+
+use std::{
+    fs::{create_dir_all, remove_file, write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{sleep, spawn},
+    time::{Duration, Instant},
+};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn configuration_json(name: &str) -> String {
+    let configuration = Configuration {
+        id: "avtr_00000000-0000-0000-0000-000000000000".into(),
+        name: name.into(),
+        parameters: vec![],
+        unknown_fields: Default::default(),
+    };
+    serde_json::to_string(&configuration).expect("Should serialize")
+}
+
+#[test]
+fn test_watch() {
+    let temp = TempDir::new("watch");
+    let root = temp.path();
+    let avatars = root.join("usr_a/Avatars");
+    create_dir_all(&avatars).expect("Should create");
+    let existing = avatars.join("avtr_existing.json");
+    write(&existing, configuration_json("Existing")).expect("Should write");
+
+    let mut watcher =
+        ConfigWatcher::new(OscDir::new(root), Duration::from_millis(100)).expect("Should watch");
+    assert_eq!(watcher.configurations().count(), 1);
+
+    let path = avatars.join("avtr_00000000-0000-0000-0000-000000000000.json");
+    write(&path, configuration_json("First")).expect("Should write");
+    match watcher.recv_timeout(TIMEOUT) {
+        Some(Ok(ConfigEvent::Added {
+            path: p,
+            configuration,
+        })) => {
+            assert_eq!(p, path);
+            assert_eq!(configuration.name, "First");
+        }
+        otherwise => panic!("Unexpected event: {:?}", otherwise),
+    }
+
+    write(avatars.join("ignored.json"), "{}").expect("Should write");
+    write(&path, configuration_json("Second")).expect("Should write");
+    match watcher.recv_timeout(TIMEOUT) {
+        Some(Ok(ConfigEvent::Modified {
+            path: p,
+            configuration,
+        })) => {
+            assert_eq!(p, path);
+            assert_eq!(configuration.name, "Second");
+        }
+        otherwise => panic!("Unexpected event: {:?}", otherwise),
+    }
+
+    remove_file(&existing).expect("Should remove");
+    assert_eq!(
+        watcher
+            .recv_timeout(TIMEOUT)
+            .map(|e| e.expect("Should valid")),
+        Some(ConfigEvent::Removed { path: existing })
+    );
+    assert_eq!(watcher.configurations().count(), 1);
+}
+
+#[test]
+fn test_watch_timeout() {
+    let temp = TempDir::new("watch_timeout");
+    let root = temp.path();
+    let avatars = root.join("usr_a/Avatars");
+    create_dir_all(&avatars).expect("Should create");
+
+    let mut watcher =
+        ConfigWatcher::new(OscDir::new(root), Duration::from_millis(50)).expect("Should watch");
+
+    // Files other than configurations keep changing for a while, which must not extend the timeout.
+    let started = Instant::now();
+    let stopped = Arc::new(AtomicBool::new(false));
+    let writer = {
+        let stopped = stopped.clone();
+        let ignored = avatars.join("ignored.json");
+        spawn(move || {
+            while !stopped.load(Ordering::Relaxed) && started.elapsed() < Duration::from_secs(3) {
+                write(&ignored, "{}").expect("Should write");
+                sleep(Duration::from_millis(20));
+            }
+        })
+    };
+
+    assert!(watcher.recv_timeout(Duration::from_millis(500)).is_none());
+    let elapsed = started.elapsed();
+    stopped.store(true, Ordering::Relaxed);
+    writer.join().expect("Should join");
+    assert!(elapsed < Duration::from_millis(1500), "{:?}", elapsed);
+}
@@ -1,12 +1,5 @@
-use std::collections::HashMap;
-
-use async_std::{
-    net::SocketAddr,
-    sync::{Arc, Mutex},
-};
+use async_std::net::SocketAddr;
 use clap::Parser;
-use phorcys_config::vrchat::config::Configuration;
-use phorcys_osc::data::Value;
 
 /// Receives OSC packets from VRChat and provides querying interface.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Parser)]
@@ -59,12 +52,3 @@ pub struct ProxyMidiArguments {
     /// Filename of entries table.
     pub entries_table: String,
 }
-
-#[derive(Debug, Clone)]
-pub struct Application {
-    /// OSC packet source configuration.
-    pub configuration: Arc<Configuration>,
-
-    /// Latest values received from VRChat.
-    pub latest_values: Arc<Mutex<HashMap<String, Value>>>,
-}
@@ -11,7 +11,7 @@ use std::{
 
 use anyhow::{bail, Result};
 use async_std::{
-    fs::read_to_string,
+    fs::{read, read_to_string},
     net::UdpSocket,
     sync::Arc,
     task::{sleep, spawn},
@@ -33,9 +33,9 @@ struct Context {
 /// proxy-midi subcommand.
 pub async fn proxy_midi(args: ProxyMidiArguments) -> Result<()> {
     // Read configs
-    let avatar_config_source = read_to_string(args.avatar_configuration).await?;
+    let avatar_config_source = read(args.avatar_configuration).await?;
     let parameters_table_source = read_to_string(args.entries_table).await?;
-    let avatar_config: VrcConfiguration = serde_json::from_str(&avatar_config_source)?;
+    let avatar_config = VrcConfiguration::from_slice(&avatar_config_source)?;
     let parameters_table: ParametersTable = toml::from_str(&parameters_table_source)?;
 
     // Avatar ID validation
@@ -137,16 +137,16 @@ async fn process_midi_to_entry(context: Arc<Context>, channel: u8, key: u8) {
                 continue;
             }
         };
-        let target_address = match &target_param.input {
-            Some(input) => &input.address,
+        let (target_address, target_type) = match &target_param.input {
+            Some(input) => (&input.address, &input.parameter_type),
             None => {
                 warn!("Parameter \"{}\" does not have input", param_name);
                 continue;
             }
         };
-        let param_value = match &value {
-            toml::Value::Integer(i) => OscValue::Int32(*i as i32),
-            toml::Value::Float(f) => OscValue::Float32(*f as f32),
+        let table_value = match &value {
+            toml::Value::Integer(i) => OscValue::Int64(*i),
+            toml::Value::Float(f) => OscValue::Float64(*f),
             toml::Value::Boolean(b) => OscValue::Boolean(*b),
             _ => {
                 warn!(
@@ -156,6 +156,13 @@ async fn process_midi_to_entry(context: Arc<Context>, channel: u8, key: u8) {
                 continue;
             }
         };
+        let param_value = match VrcParameterValue::coerce(&table_value, target_type) {
+            Ok(v) => OscValue::from(v),
+            Err(e) => {
+                warn!("Invalid value for parameter \"{}\": {}", param_name, e);
+                continue;
+            }
+        };
 
         let message = match OscMessageBuilder::new(target_address) {
             Ok(b) => b.push_argument(param_value).build(),
@@ -0,0 +1,77 @@
+//! Benchmarks of encoding and decoding typical VRChat packets.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use phorcys_osc::{data::TimeTag, prelude::*};
+
+/// Small messages, like avatar parameters sent many times per second.
+fn parameter_messages() -> Vec<(&'static str, OscPacket)> {
+    let message = |address: &str, value: OscValue| {
+        OscPacket::Message(
+            OscMessageBuilder::new(address)
+                .expect("Should valid")
+                .push_argument(value)
+                .build(),
+        )
+    };
+
+    vec![
+        (
+            "float",
+            message("/avatar/parameters/VelocityZ", OscValue::Float32(0.5)),
+        ),
+        (
+            "bool",
+            message("/avatar/parameters/Grounded", OscValue::Boolean(true)),
+        ),
+        (
+            "string",
+            message("/chatbox/input", OscValue::String("Hello, world".into())),
+        ),
+    ]
+}
+
+/// A bundle of several parameters.
+fn parameter_bundle() -> OscPacket {
+    let elements = parameter_messages()
+        .into_iter()
+        .map(|(_, packet)| packet)
+        .collect();
+    OscPacket::Bundle(OscBundle::new(TimeTag::IMMEDIATELY, elements))
+}
+
+fn bench_encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    group.throughput(Throughput::Elements(1));
+
+    let mut cases = parameter_messages();
+    cases.push(("bundle", parameter_bundle()));
+    for (name, packet) in cases {
+        group.bench_function(format!("serialize/{}", name), |b| {
+            b.iter(|| black_box(packet.clone()).serialize())
+        });
+
+        let mut buffer = vec![0; 1024];
+        group.bench_function(format!("encode_into/{}", name), |b| {
+            b.iter(|| black_box(&packet).encode_into(&mut buffer))
+        });
+    }
+    group.finish();
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    group.throughput(Throughput::Elements(1));
+
+    let mut cases = parameter_messages();
+    cases.push(("bundle", parameter_bundle()));
+    for (name, packet) in cases {
+        let bytes = packet.serialize();
+        group.bench_function(format!("deserialize/{}", name), |b| {
+            b.iter(|| OscPacket::deserialize(black_box(&bytes)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_encode, bench_decode);
+criterion_main!(benches);
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use phorcys_osc::address::AddressPattern;
+
+fuzz_target!(|pattern: &str| {
+    let _ = AddressPattern::parse(pattern);
+    let _ = AddressPattern::compile_to_regex(pattern);
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use phorcys_osc::packet::Packet;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(packet) = Packet::deserialize(data) {
+        // Accepted packets must be serializable again.
+        let _ = packet.serialize();
+    }
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use phorcys_osc::packet::Packet;
+
+fuzz_target!(|packet: Packet| {
+    let bytes = packet.clone().serialize();
+    assert_eq!(Packet::deserialize(&bytes), Ok(packet));
+});
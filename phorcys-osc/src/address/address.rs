@@ -2,71 +2,303 @@
 
 use crate::error::{Error, Result};
 
-use std::fmt::{Display, Formatter, Result as FmtResult};
+use alloc::{borrow::ToOwned, string::String};
+use core::{
+    borrow::Borrow,
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::Deref,
+    str::{FromStr, Split},
+};
+
+#[cfg(feature = "serde")]
+use core::result::Result as StdResult;
 
 /// Prohibited characters in OSC method part.
 /// **Slash contained**.
 pub const METHOD_PROHIBITED_CHAR: [char; 10] = [' ', '#', '*', ',', '/', '?', '[', ']', '{', '}'];
 
-/// A valid OSC address, reference form.
+/// Owned OSC address, kept for compatibility.
+pub type Address = AddressBuf;
+
+/// A valid OSC address, owned form.
 /// It does not express an OSC address pattern.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Address(String);
+pub struct AddressBuf(String);
 
-impl Address {
-    /// Checks validity and wraps into `Address`.
-    pub fn new(address: &str) -> Result<Address> {
-        if !address.starts_with('/') || !address.is_ascii() {
-            return Err(Error::InvalidAddress);
-        }
-        if address[1..]
-            .split('/')
-            .any(|p| p.is_empty() || p.find(METHOD_PROHIBITED_CHAR).is_some())
-        {
-            return Err(Error::InvalidAddress);
-        }
+/// A valid OSC address, borrowed form.
+/// It relates to `AddressBuf` as `str` does to `String`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct AddressStr(str);
 
-        Ok(Address(address.into()))
+/// Checks whether `part` is a valid OSC address part.
+fn validate_part(part: &str) -> Result<()> {
+    if part.is_empty() || !part.is_ascii() || part.find(METHOD_PROHIBITED_CHAR).is_some() {
+        return Err(Error::InvalidAddress);
+    }
+    Ok(())
+}
+
+impl AddressBuf {
+    /// Checks validity and wraps into `AddressBuf`.
+    pub fn new(address: &str) -> Result<AddressBuf> {
+        Ok(AddressStr::new(address)?.to_address_buf())
     }
 
     /// Pushes OSC address part.
     pub fn push_part(&mut self, part: &str) -> Result<()> {
-        if part.is_empty() || !part.is_ascii() || part.find(METHOD_PROHIBITED_CHAR).is_some() {
-            return Err(Error::InvalidAddress);
-        }
+        validate_part(part)?;
 
         self.0.push('/');
         self.0.push_str(part);
         Ok(())
     }
 
+    /// Removes the last part.
+    /// Returns `false` and does nothing if this address has only one part.
+    pub fn pop_part(&mut self) -> bool {
+        match self.parent().map(|p| p.0.len()) {
+            Some(length) => {
+                self.0.truncate(length);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns borrowed form.
+    pub fn as_address_str(&self) -> &AddressStr {
+        AddressStr::from_str_unchecked(&self.0)
+    }
+
     /// Consume itself and return inner `String`.
     pub fn into_string(self) -> String {
         self.0
     }
 }
 
-impl AsRef<str> for Address {
+impl AddressStr {
+    /// Checks validity and wraps into `AddressStr`.
+    pub fn new(address: &str) -> Result<&AddressStr> {
+        match address.strip_prefix('/') {
+            Some(parts) => {
+                for part in parts.split('/') {
+                    validate_part(part)?;
+                }
+                Ok(AddressStr::from_str_unchecked(address))
+            }
+            None => Err(Error::InvalidAddress),
+        }
+    }
+
+    /// Wraps `address` without validation.
+    fn from_str_unchecked(address: &str) -> &AddressStr {
+        // SAFETY: AddressStr is `repr(transparent)` over `str`.
+        unsafe { &*(address as *const str as *const AddressStr) }
+    }
+
+    /// Returns the address string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Copies into owned form.
+    pub fn to_address_buf(&self) -> AddressBuf {
+        AddressBuf(self.0.into())
+    }
+
+    /// Iterates over address parts.
+    /// `/avatar/parameters/Foo` yields `avatar`, `parameters` and `Foo`.
+    pub fn segments(&self) -> Split<'_, char> {
+        self.0[1..].split('/')
+    }
+
+    /// Returns the address without the last part.
+    /// Returns `None` if this address has only one part.
+    pub fn parent(&self) -> Option<&AddressStr> {
+        match self.0.rfind('/') {
+            Some(0) | None => None,
+            Some(i) => Some(AddressStr::from_str_unchecked(&self.0[..i])),
+        }
+    }
+
+    /// Returns the last part, such as the method name.
+    pub fn last_segment(&self) -> &str {
+        match self.0.rfind('/') {
+            Some(i) => &self.0[(i + 1)..],
+            None => &self.0,
+        }
+    }
+
+    /// Creates an address with `path` appended.
+    /// `path` is one or more parts separated by slashes, such as `parameters/Foo`.
+    pub fn join(&self, path: &str) -> Result<AddressBuf> {
+        let mut joined = self.to_address_buf();
+        for part in path.split('/') {
+            joined.push_part(part)?;
+        }
+        Ok(joined)
+    }
+
+    /// Whether `base` is this address or its ancestor.
+    /// Only whole parts are compared, so `/foo/bar` does not start with `/foo/b`.
+    pub fn starts_with(&self, base: &AddressStr) -> bool {
+        match self.0.strip_prefix(&base.0) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/'),
+            None => false,
+        }
+    }
+
+    /// Returns the rest of this address after `base`.
+    /// Returns `None` if `base` is not an ancestor of this address, including the case they are equal.
+    pub fn strip_prefix(&self, base: &AddressStr) -> Option<&AddressStr> {
+        match self.0.strip_prefix(&base.0) {
+            Some(rest) if rest.starts_with('/') => Some(AddressStr::from_str_unchecked(rest)),
+            _ => None,
+        }
+    }
+}
+
+impl Deref for AddressBuf {
+    type Target = AddressStr;
+
+    fn deref(&self) -> &AddressStr {
+        self.as_address_str()
+    }
+}
+
+impl Borrow<AddressStr> for AddressBuf {
+    fn borrow(&self) -> &AddressStr {
+        self.as_address_str()
+    }
+}
+
+impl ToOwned for AddressStr {
+    type Owned = AddressBuf;
+
+    fn to_owned(&self) -> AddressBuf {
+        self.to_address_buf()
+    }
+}
+
+impl AsRef<str> for AddressBuf {
     fn as_ref(&self) -> &str {
         &self.0
     }
 }
 
-impl From<Address> for String {
-    fn from(x: Address) -> Self {
+impl AsRef<str> for AddressStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<AddressStr> for AddressBuf {
+    fn as_ref(&self) -> &AddressStr {
+        self.as_address_str()
+    }
+}
+
+impl AsRef<AddressStr> for AddressStr {
+    fn as_ref(&self) -> &AddressStr {
+        self
+    }
+}
+
+impl From<&AddressStr> for AddressBuf {
+    fn from(x: &AddressStr) -> Self {
+        x.to_address_buf()
+    }
+}
+
+impl From<AddressBuf> for String {
+    fn from(x: AddressBuf) -> Self {
         x.0
     }
 }
 
-impl Display for Address {
+impl TryFrom<String> for AddressBuf {
+    type Error = Error;
+
+    fn try_from(address: String) -> Result<AddressBuf> {
+        AddressStr::new(&address)?;
+        Ok(AddressBuf(address))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for &'a AddressStr {
+    type Error = Error;
+
+    fn try_from(address: &'a str) -> Result<&'a AddressStr> {
+        AddressStr::new(address)
+    }
+}
+
+impl FromStr for AddressBuf {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<AddressBuf> {
+        AddressBuf::new(s)
+    }
+}
+
+impl PartialEq<AddressStr> for AddressBuf {
+    fn eq(&self, other: &AddressStr) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl PartialEq<AddressBuf> for AddressStr {
+    fn eq(&self, other: &AddressBuf) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl PartialEq<&AddressStr> for AddressBuf {
+    fn eq(&self, other: &&AddressStr) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Display for AddressBuf {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         self.0.fmt(f)
     }
 }
 
+impl Display for AddressStr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AddressStr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AddressBuf {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AddressBuf {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> StdResult<Self, D::Error> {
+        let address = String::deserialize(deserializer)?;
+        AddressBuf::try_from(address).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::Address;
+    use super::{Address, AddressBuf, AddressStr};
+
+    use alloc::{borrow::ToOwned, string::String, vec, vec::Vec};
 
     /// Ensures valid OSC addresses are accepted.
     #[test]
@@ -88,4 +320,76 @@ mod test {
         assert!(Address::new("/address/to*").is_err());
         assert!(Address::new("/address/to osc method").is_err());
     }
+
+    /// Ensures hierarchical path operations.
+    #[test]
+    fn test_address_hierarchy() {
+        let address = AddressStr::new("/avatar/parameters/Foo").expect("Should valid");
+        let base = AddressStr::new("/avatar/parameters").expect("Should valid");
+
+        assert_eq!(
+            address.segments().collect::<Vec<_>>(),
+            vec!["avatar", "parameters", "Foo"]
+        );
+        assert_eq!(address.parent(), Some(base));
+        assert_eq!(
+            base.parent(),
+            Some(AddressStr::new("/avatar").expect("Should valid"))
+        );
+        assert_eq!(
+            AddressStr::new("/avatar").expect("Should valid").parent(),
+            None
+        );
+        assert_eq!(address.last_segment(), "Foo");
+
+        assert!(address.starts_with(base));
+        assert!(address.starts_with(address));
+        assert!(!address.starts_with(AddressStr::new("/avatar/param").expect("Should valid")));
+        assert!(!base.starts_with(address));
+
+        assert_eq!(address.strip_prefix(base).map(|a| a.as_str()), Some("/Foo"));
+        assert_eq!(address.strip_prefix(address), None);
+        assert_eq!(base.strip_prefix(address), None);
+
+        assert_eq!(base.join("Foo").expect("Should valid"), address);
+        assert_eq!(
+            AddressStr::new("/avatar")
+                .expect("Should valid")
+                .join("parameters/Foo")
+                .expect("Should valid"),
+            address
+        );
+        assert!(base.join("Foo/").is_err());
+        assert!(base.join("Foo Bar").is_err());
+    }
+
+    /// Ensures conversions between owned and borrowed forms.
+    #[test]
+    fn test_address_conversion() {
+        let mut owned: AddressBuf = "/avatar/parameters".parse().expect("Should valid");
+        owned.push_part("Foo").expect("Should valid");
+        let borrowed: &AddressStr = &owned;
+        assert_eq!(borrowed.as_str(), "/avatar/parameters/Foo");
+        assert_eq!(borrowed.to_owned(), owned);
+
+        assert!(owned.pop_part());
+        assert_eq!(owned.as_str(), "/avatar/parameters");
+        assert!(owned.pop_part());
+        assert!(!owned.pop_part());
+        assert_eq!(owned.into_string(), "/avatar");
+
+        assert!("/avatar/".parse::<AddressBuf>().is_err());
+        assert!(AddressBuf::try_from(String::from("/avatar")).is_ok());
+    }
+
+    /// Ensures addresses are serialized as strings and validated on deserialization.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_address_serde() {
+        use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+        let address = AddressBuf::new("/avatar/change").expect("Should valid");
+        assert_tokens(&address, &[Token::Str("/avatar/change")]);
+        assert_de_tokens_error::<AddressBuf>(&[Token::Str("/avatar/")], "Invalid address");
+    }
 }
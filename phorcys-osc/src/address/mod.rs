@@ -1,11 +1,12 @@
 //! Contains manipulations of address and address patterns.
 
+#[allow(clippy::module_inception)]
 pub mod address;
 
 #[cfg(feature = "address-pattern")]
 pub mod pattern;
 
-pub use crate::address::address::Address;
+pub use crate::address::address::{Address, AddressBuf, AddressStr};
 
 #[cfg(feature = "address-pattern")]
 pub use crate::address::pattern::{AddressPattern, Expression};
@@ -45,6 +45,33 @@ impl AddressPattern {
 
         Regex::new(&regex_string).map_err(|e| Error::InvalidPattern(e.to_string()))
     }
+
+    /// Parses an address pattern string and compiles into `regex::Regex` with capture groups.
+    /// Each non-literal expression becomes a capture group numbered from 1 in order of appearance.
+    ///
+    /// The same caution as `compile_to_regex` applies.
+    pub fn compile_to_capturing_regex(pattern: &str) -> Result<Regex> {
+        let expression_tree = AddressPattern::parse(pattern)?;
+
+        let mut regex_string = String::new();
+        regex_string.push('^');
+        for method_part in expression_tree {
+            regex_string.push('/');
+            for expression in method_part {
+                match expression {
+                    Expression::Literal(_) => expression.push_regex_part(&mut regex_string),
+                    _ => {
+                        regex_string.push('(');
+                        expression.push_regex_part(&mut regex_string);
+                        regex_string.push(')');
+                    }
+                }
+            }
+        }
+        regex_string.push('$');
+
+        Regex::new(&regex_string).map_err(|e| Error::InvalidPattern(e.to_string()))
+    }
 }
 
 /// Expression a part in OSC Address Pattern.
@@ -313,4 +340,17 @@ mod test {
             Regex::new(r#"^/foo(?:.*)/com[A-Za-z](?:ine|ination)..$"#).expect("Should be success");
         assert_eq!(parsed.to_string(), expected.to_string());
     }
+
+    #[test]
+    fn test_capturing_regex_compilation() {
+        let parsed =
+            AddressPattern::compile_to_capturing_regex("/foo/*/[ab]?").expect("Should be success");
+        let expected = Regex::new(r#"^/foo/((?:.*))/([ab])(.)$"#).expect("Should be success");
+        assert_eq!(parsed.to_string(), expected.to_string());
+
+        let captures = parsed.captures("/foo/bar/ax").expect("Should match");
+        assert_eq!(&captures[1], "bar");
+        assert_eq!(&captures[2], "a");
+        assert_eq!(&captures[3], "x");
+    }
 }
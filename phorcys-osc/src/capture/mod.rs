@@ -0,0 +1,52 @@
+//! Packet capture file format, recording and replaying.
+//! Captures from pcap and pcapng files can also be imported.
+
+pub mod pcap;
+pub mod record;
+pub mod replay;
+
+pub use crate::capture::{
+    pcap::PcapReader,
+    record::{CaptureReader, CaptureWriter, Record, CAPTURE_MAGIC, CAPTURE_VERSION},
+    replay::Replayer,
+};
+
+use std::{io::Error as IoError, result::Result as StdResult};
+
+use thiserror::Error as ThisError;
+
+/// Represents an error in capture file manipulations.
+#[non_exhaustive]
+#[derive(Debug, ThisError)]
+pub enum CaptureError {
+    /// I/O error in underlying stream or socket.
+    #[error("I/O error: {0}")]
+    Io(#[from] IoError),
+
+    /// Capture file header is broken.
+    #[error("Invalid capture header")]
+    InvalidHeader,
+
+    /// Capture file has unsupported version.
+    #[error("Unsupported capture version: {0}")]
+    UnsupportedVersion(u32),
+
+    /// Capture record is broken.
+    #[error("Invalid capture record")]
+    InvalidRecord,
+
+    /// Replaying speed is not a positive finite number, or too slow to schedule.
+    #[error("Invalid replay speed: {0}")]
+    InvalidSpeed(f64),
+
+    /// Link-layer type in pcap file is not supported.
+    #[error("Unsupported link type: {0}")]
+    UnsupportedLinkType(u32),
+
+    /// Recorded bytes are not a valid OSC packet.
+    #[error("OSC error: {0}")]
+    Osc(#[from] crate::error::Error),
+}
+
+/// Result type shorthand with `CaptureError`.
+pub type CaptureResult<T> = StdResult<T, CaptureError>;
@@ -0,0 +1,803 @@
+//! Reader of pcap and pcapng files.
+//!
+//! Only UDP datagrams carried on Ethernet, BSD loopback, Linux cooked capture or raw IP
+//! link types are extracted. IP fragments are not reassembled and are skipped.
+
+use crate::{
+    capture::{CaptureError, CaptureResult, Record},
+    packet::Packet,
+};
+
+use std::{
+    io::{ErrorKind as IoErrorKind, Read},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Magic number of pcap file with microsecond timestamps.
+const PCAP_MAGIC_MICROS: u32 = 0xA1B2C3D4;
+
+/// Magic number of pcap file with nanosecond timestamps.
+const PCAP_MAGIC_NANOS: u32 = 0xA1B23C4D;
+
+/// Block type of pcapng Section Header Block.
+const PCAPNG_SECTION_HEADER: u32 = 0x0A0D0D0A;
+
+/// Byte-order magic of pcapng Section Header Block.
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1A2B3C4D;
+
+/// Block type of pcapng Interface Description Block.
+const PCAPNG_INTERFACE_DESCRIPTION: u32 = 0x00000001;
+
+/// Block type of pcapng Enhanced Packet Block.
+const PCAPNG_ENHANCED_PACKET: u32 = 0x00000006;
+
+/// `LINKTYPE_NULL`: BSD loopback.
+const LINKTYPE_NULL: u32 = 0;
+
+/// `LINKTYPE_ETHERNET`.
+const LINKTYPE_ETHERNET: u32 = 1;
+
+/// `LINKTYPE_RAW`: raw IPv4 or IPv6.
+const LINKTYPE_RAW: u32 = 101;
+
+/// `LINKTYPE_LOOP`: OpenBSD loopback.
+const LINKTYPE_LOOP: u32 = 108;
+
+/// `LINKTYPE_LINUX_SLL`: Linux cooked capture v1.
+const LINKTYPE_LINUX_SLL: u32 = 113;
+
+/// `LINKTYPE_IPV4`.
+const LINKTYPE_IPV4: u32 = 228;
+
+/// `LINKTYPE_IPV6`.
+const LINKTYPE_IPV6: u32 = 229;
+
+/// `LINKTYPE_LINUX_SLL2`: Linux cooked capture v2.
+const LINKTYPE_LINUX_SLL2: u32 = 276;
+
+/// EtherType of IPv4.
+const ETHERTYPE_IPV4: u16 = 0x0800;
+
+/// EtherType of IPv6.
+const ETHERTYPE_IPV6: u16 = 0x86DD;
+
+/// Reads UDP datagrams from pcap or pcapng file.
+/// The format is detected from the file header.
+#[derive(Debug)]
+pub struct PcapReader<R> {
+    reader: R,
+    format: Format,
+    port: Option<u16>,
+}
+
+/// Detected file format and its state.
+#[derive(Debug, Clone)]
+enum Format {
+    /// Classic pcap.
+    Pcap {
+        big_endian: bool,
+        nanosecond: bool,
+        link_type: u32,
+    },
+
+    /// pcapng, with interfaces defined in current section.
+    PcapNg {
+        big_endian: bool,
+        interfaces: Vec<Interface>,
+    },
+}
+
+/// Interface description in pcapng.
+#[derive(Debug, Clone, Copy)]
+struct Interface {
+    link_type: u32,
+
+    /// Timestamp units per second.
+    resolution: u64,
+}
+
+impl<R: Read> PcapReader<R> {
+    /// Creates a reader and detects the file format.
+    pub fn new(mut reader: R) -> CaptureResult<PcapReader<R>> {
+        let magic: [u8; 4] = read_array(&mut reader, CaptureError::InvalidHeader)?;
+        let format = match (u32::from_be_bytes(magic), u32::from_le_bytes(magic)) {
+            (PCAPNG_SECTION_HEADER, _) => {
+                let big_endian = read_section_header(&mut reader)?;
+                Format::PcapNg {
+                    big_endian,
+                    interfaces: vec![],
+                }
+            }
+            (m, _) if m == PCAP_MAGIC_MICROS || m == PCAP_MAGIC_NANOS => {
+                read_pcap_header(&mut reader, true, m == PCAP_MAGIC_NANOS)?
+            }
+            (_, m) if m == PCAP_MAGIC_MICROS || m == PCAP_MAGIC_NANOS => {
+                read_pcap_header(&mut reader, false, m == PCAP_MAGIC_NANOS)?
+            }
+            _ => return Err(CaptureError::InvalidHeader),
+        };
+
+        Ok(PcapReader {
+            reader,
+            format,
+            port: None,
+        })
+    }
+
+    /// Accepts only datagrams sent to `port`.
+    pub fn port(mut self, port: u16) -> PcapReader<R> {
+        self.port = Some(port);
+        self
+    }
+
+    /// Reads the next UDP datagram which passes the port filter.
+    /// Returns `None` if the stream reached its end.
+    pub fn read_record(&mut self) -> CaptureResult<Option<Record>> {
+        loop {
+            let frame = match self.format {
+                Format::Pcap { .. } => self.read_pcap_frame()?,
+                Format::PcapNg { .. } => self.read_pcapng_frame()?,
+            };
+            let (timestamp, link_type, data) = match frame {
+                Some(Some(frame)) => frame,
+                Some(None) => continue,
+                None => return Ok(None),
+            };
+
+            let (source, destination_port, payload) = match decode_udp(link_type, &data)? {
+                Some(datagram) => datagram,
+                None => continue,
+            };
+            if self.port.map(|p| p != destination_port).unwrap_or(false) {
+                continue;
+            }
+
+            return Ok(Some(Record {
+                timestamp,
+                source,
+                bytes: payload.into(),
+            }));
+        }
+    }
+
+    /// Consumes itself and returns an iterator of deserialized packets.
+    pub fn packets(self) -> impl Iterator<Item = CaptureResult<(SystemTime, SocketAddr, Packet)>> {
+        self.map(|record| {
+            let record = record?;
+            let packet = record.packet()?;
+            Ok((record.timestamp, record.source, packet))
+        })
+    }
+
+    /// Reads a pcap record.
+    /// The inner `None` means a frame that should be skipped.
+    #[allow(clippy::type_complexity)]
+    fn read_pcap_frame(&mut self) -> CaptureResult<Option<Option<(SystemTime, u32, Vec<u8>)>>> {
+        let (big_endian, nanosecond, link_type) = match self.format {
+            Format::Pcap {
+                big_endian,
+                nanosecond,
+                link_type,
+            } => (big_endian, nanosecond, link_type),
+            _ => unreachable!("Not a pcap"),
+        };
+
+        let header: [u8; 16] = match read_array_or_eof(&mut self.reader)? {
+            Some(h) => h,
+            None => return Ok(None),
+        };
+        let seconds = read_u32(&header[0..4], big_endian);
+        let fraction = read_u32(&header[4..8], big_endian);
+        let captured_length = read_u32(&header[8..12], big_endian) as usize;
+        let data = read_vec(&mut self.reader, captured_length)?;
+
+        let fraction = if nanosecond {
+            Duration::from_nanos(fraction as u64)
+        } else {
+            Duration::from_micros(fraction as u64)
+        };
+        let timestamp = UNIX_EPOCH + Duration::from_secs(seconds as u64) + fraction;
+        Ok(Some(Some((timestamp, link_type, data))))
+    }
+
+    /// Reads a pcapng block.
+    /// The inner `None` means a block that does not contain a frame.
+    #[allow(clippy::type_complexity)]
+    fn read_pcapng_frame(&mut self) -> CaptureResult<Option<Option<(SystemTime, u32, Vec<u8>)>>> {
+        let block_type: [u8; 4] = match read_array_or_eof(&mut self.reader)? {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+
+        // A new section may change the byte order.
+        if u32::from_be_bytes(block_type) == PCAPNG_SECTION_HEADER {
+            let big_endian = read_section_header(&mut self.reader)?;
+            self.format = Format::PcapNg {
+                big_endian,
+                interfaces: vec![],
+            };
+            return Ok(Some(None));
+        }
+
+        let (big_endian, interfaces) = match &mut self.format {
+            Format::PcapNg {
+                big_endian,
+                interfaces,
+            } => (*big_endian, interfaces),
+            _ => unreachable!("Not a pcapng"),
+        };
+        let block_type = read_u32(&block_type, big_endian);
+        let total_length: [u8; 4] = read_array(&mut self.reader, CaptureError::InvalidRecord)?;
+        let total_length = read_u32(&total_length, big_endian) as usize;
+        if total_length < 12 || !total_length.is_multiple_of(4) {
+            return Err(CaptureError::InvalidRecord);
+        }
+        let body = read_vec(&mut self.reader, total_length - 8)?;
+        let body = &body[..(total_length - 12)];
+
+        match block_type {
+            PCAPNG_INTERFACE_DESCRIPTION => {
+                if body.len() < 8 {
+                    return Err(CaptureError::InvalidRecord);
+                }
+                let link_type = read_u16(&body[0..2], big_endian) as u32;
+                let resolution = read_interface_resolution(&body[8..], big_endian)?;
+                interfaces.push(Interface {
+                    link_type,
+                    resolution,
+                });
+                Ok(Some(None))
+            }
+            PCAPNG_ENHANCED_PACKET => {
+                if body.len() < 20 {
+                    return Err(CaptureError::InvalidRecord);
+                }
+                let interface_id = read_u32(&body[0..4], big_endian) as usize;
+                let interface = interfaces
+                    .get(interface_id)
+                    .ok_or(CaptureError::InvalidRecord)?;
+                let ticks = ((read_u32(&body[4..8], big_endian) as u64) << 32)
+                    | read_u32(&body[8..12], big_endian) as u64;
+                let captured_length = read_u32(&body[12..16], big_endian) as usize;
+                let data = body
+                    .get(20..(20 + captured_length))
+                    .ok_or(CaptureError::InvalidRecord)?;
+
+                let seconds = ticks / interface.resolution;
+                let nanos = (ticks % interface.resolution) as u128 * 1_000_000_000
+                    / interface.resolution as u128;
+                let timestamp = UNIX_EPOCH
+                    .checked_add(Duration::new(seconds, nanos as u32))
+                    .ok_or(CaptureError::InvalidRecord)?;
+                Ok(Some(Some((timestamp, interface.link_type, data.into()))))
+            }
+            _ => Ok(Some(None)),
+        }
+    }
+}
+
+impl<R: Read> Iterator for PcapReader<R> {
+    type Item = CaptureResult<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Reads the rest of classic pcap header after the magic number.
+fn read_pcap_header(
+    reader: &mut impl Read,
+    big_endian: bool,
+    nanosecond: bool,
+) -> CaptureResult<Format> {
+    let header: [u8; 20] = read_array(reader, CaptureError::InvalidHeader)?;
+    let link_type = read_u32(&header[16..20], big_endian) & 0x0FFFFFFF;
+    Ok(Format::Pcap {
+        big_endian,
+        nanosecond,
+        link_type,
+    })
+}
+
+/// Reads the rest of Section Header Block after the block type.
+/// Returns whether the section is big endian.
+fn read_section_header(reader: &mut impl Read) -> CaptureResult<bool> {
+    let header: [u8; 8] = read_array(reader, CaptureError::InvalidHeader)?;
+    let big_endian = match (
+        u32::from_be_bytes(header[4..8].try_into().expect("Wrong length")),
+        u32::from_le_bytes(header[4..8].try_into().expect("Wrong length")),
+    ) {
+        (PCAPNG_BYTE_ORDER_MAGIC, _) => true,
+        (_, PCAPNG_BYTE_ORDER_MAGIC) => false,
+        _ => return Err(CaptureError::InvalidHeader),
+    };
+    let total_length = read_u32(&header[0..4], big_endian) as usize;
+    if total_length < 16 || !total_length.is_multiple_of(4) {
+        return Err(CaptureError::InvalidHeader);
+    }
+
+    read_vec(reader, total_length - 12)?;
+    Ok(big_endian)
+}
+
+/// Reads `if_tsresol` option from options in Interface Description Block.
+fn read_interface_resolution(mut options: &[u8], big_endian: bool) -> CaptureResult<u64> {
+    while options.len() >= 4 {
+        let code = read_u16(&options[0..2], big_endian);
+        let length = read_u16(&options[2..4], big_endian) as usize;
+        let padded_length = (length + 3) & !3;
+        let value = options
+            .get(4..(4 + length))
+            .ok_or(CaptureError::InvalidRecord)?;
+
+        match code {
+            0 => break,
+            9 if length == 1 => {
+                let exponent = (value[0] & 0x7F) as u32;
+                let resolution = if value[0] & 0x80 == 0 {
+                    10u64.checked_pow(exponent)
+                } else {
+                    2u64.checked_pow(exponent)
+                };
+                return resolution.ok_or(CaptureError::InvalidRecord);
+            }
+            _ => (),
+        }
+        options = options.get((4 + padded_length)..).unwrap_or_default();
+    }
+
+    Ok(1_000_000)
+}
+
+/// Extracts UDP datagram from a link-layer frame.
+/// Returns source address, destination port and payload.
+fn decode_udp(link_type: u32, frame: &[u8]) -> CaptureResult<Option<(SocketAddr, u16, &[u8])>> {
+    // EtherType is `None` for link types without it, where IP version is detected from the packet.
+    let link_payload = match link_type {
+        LINKTYPE_NULL | LINKTYPE_LOOP => frame.get(4..).map(|p| (None, p)),
+        LINKTYPE_ETHERNET => {
+            let mut rest = frame.get(12..);
+            // Skips 802.1Q tags.
+            while let Some(r) = rest {
+                match r.get(0..2) {
+                    Some([0x81, 0x00]) | Some([0x88, 0xA8]) => rest = r.get(4..),
+                    Some(_) => break,
+                    None => rest = None,
+                }
+            }
+            rest.and_then(|r| split_ethertype(r, 0, 2))
+        }
+        LINKTYPE_LINUX_SLL => split_ethertype(frame, 14, 16),
+        LINKTYPE_LINUX_SLL2 => split_ethertype(frame, 0, 20),
+        LINKTYPE_RAW | LINKTYPE_IPV4 | LINKTYPE_IPV6 => Some((None, frame)),
+        otherwise => return Err(CaptureError::UnsupportedLinkType(otherwise)),
+    };
+    let (ethertype, ip_packet) = match link_payload {
+        Some((t, p)) if !p.is_empty() => (t, p),
+        _ => return Ok(None),
+    };
+
+    let version = match ethertype {
+        Some(ETHERTYPE_IPV4) => 4,
+        Some(ETHERTYPE_IPV6) => 6,
+        Some(_) => return Ok(None),
+        None => ip_packet[0] >> 4,
+    };
+    let (source_ip, udp_segment) = match version {
+        4 => match decode_ipv4(ip_packet) {
+            Some(p) => p,
+            None => return Ok(None),
+        },
+        6 => match decode_ipv6(ip_packet) {
+            Some(p) => p,
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    if udp_segment.len() < 8 {
+        return Ok(None);
+    }
+    let source_port = u16::from_be_bytes([udp_segment[0], udp_segment[1]]);
+    let destination_port = u16::from_be_bytes([udp_segment[2], udp_segment[3]]);
+    let length = u16::from_be_bytes([udp_segment[4], udp_segment[5]]) as usize;
+    let payload = match udp_segment.get(8..length) {
+        Some(p) => p,
+        None => return Ok(None),
+    };
+
+    Ok(Some((
+        SocketAddr::new(source_ip, source_port),
+        destination_port,
+        payload,
+    )))
+}
+
+/// Reads EtherType at `position` and returns it with the payload starting at `payload_start`.
+fn split_ethertype(
+    frame: &[u8],
+    position: usize,
+    payload_start: usize,
+) -> Option<(Option<u16>, &[u8])> {
+    let ethertype = frame.get(position..(position + 2))?;
+    let payload = frame.get(payload_start..)?;
+    Some((
+        Some(u16::from_be_bytes([ethertype[0], ethertype[1]])),
+        payload,
+    ))
+}
+
+/// Extracts source address and UDP segment from IPv4 packet.
+fn decode_ipv4(packet: &[u8]) -> Option<(IpAddr, &[u8])> {
+    if packet.len() < 20 {
+        return None;
+    }
+    let header_length = ((packet[0] & 0x0F) as usize) * 4;
+    if header_length < 20 {
+        return None;
+    }
+    let total_length = u16::from_be_bytes([packet[2], packet[3]]) as usize;
+    let more_fragments = packet[6] & 0x20 != 0;
+    let fragment_offset = u16::from_be_bytes([packet[6] & 0x1F, packet[7]]);
+    if packet[9] != 17 || more_fragments || fragment_offset != 0 {
+        return None;
+    }
+
+    let source: [u8; 4] = packet[12..16].try_into().expect("Wrong length");
+    let segment = packet.get(header_length..total_length.min(packet.len()))?;
+    Some((IpAddr::V4(Ipv4Addr::from(source)), segment))
+}
+
+/// Extracts source address and UDP segment from IPv6 packet.
+/// Hop-by-hop, routing and destination options headers are skipped.
+fn decode_ipv6(packet: &[u8]) -> Option<(IpAddr, &[u8])> {
+    if packet.len() < 40 {
+        return None;
+    }
+    let payload_length = u16::from_be_bytes([packet[4], packet[5]]) as usize;
+    let source: [u8; 16] = packet[8..24].try_into().expect("Wrong length");
+    let mut next_header = packet[6];
+    let mut rest = packet.get(40..(40 + payload_length).min(packet.len()))?;
+
+    loop {
+        match next_header {
+            17 => break,
+            0 | 43 | 60 if rest.len() >= 8 => {
+                let extension_length = (rest[1] as usize + 1) * 8;
+                next_header = rest[0];
+                rest = rest.get(extension_length..)?;
+            }
+            _ => return None,
+        }
+    }
+
+    Some((IpAddr::V6(Ipv6Addr::from(source)), rest))
+}
+
+/// Reads `u16` with the byte order.
+fn read_u16(bytes: &[u8], big_endian: bool) -> u16 {
+    let bytes = bytes[..2].try_into().expect("Wrong length");
+    if big_endian {
+        u16::from_be_bytes(bytes)
+    } else {
+        u16::from_le_bytes(bytes)
+    }
+}
+
+/// Reads `u32` with the byte order.
+fn read_u32(bytes: &[u8], big_endian: bool) -> u32 {
+    let bytes = bytes[..4].try_into().expect("Wrong length");
+    if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    }
+}
+
+/// Reads `length` bytes, treating unexpected end as broken record.
+/// The buffer grows as bytes arrive, so that broken length fields do not cause huge allocation.
+fn read_vec(reader: &mut impl Read, length: usize) -> CaptureResult<Vec<u8>> {
+    let mut buffer = vec![];
+    reader.take(length as u64).read_to_end(&mut buffer)?;
+    if buffer.len() < length {
+        return Err(CaptureError::InvalidRecord);
+    }
+    Ok(buffer)
+}
+
+/// Reads fixed-length bytes, returning `on_eof` on unexpected end.
+fn read_array<const N: usize>(
+    reader: &mut impl Read,
+    on_eof: CaptureError,
+) -> CaptureResult<[u8; N]> {
+    let mut buffer = [0; N];
+    match reader.read_exact(&mut buffer) {
+        Ok(()) => Ok(buffer),
+        Err(e) if e.kind() == IoErrorKind::UnexpectedEof => Err(on_eof),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Reads fixed-length bytes, but returns `None` when the stream ends before any byte is read.
+fn read_array_or_eof<const N: usize>(reader: &mut impl Read) -> CaptureResult<Option<[u8; N]>> {
+    let mut buffer = [0; N];
+    let mut filled = 0;
+    while filled < N {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(CaptureError::InvalidRecord),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == IoErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(Some(buffer))
+}
+
+#[cfg(test)]
+mod test {
+    use super::PcapReader;
+    use crate::{
+        capture::CaptureError,
+        data::Value,
+        packet::{MessageBuilder, Packet},
+    };
+
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn osc_payload(value: f32) -> Box<[u8]> {
+        MessageBuilder::new("/avatar/parameters/Foo")
+            .expect("Should valid")
+            .push_argument(Value::Float32(value))
+            .build()
+            .serialize()
+    }
+
+    fn udp_segment(source_port: u16, destination_port: u16, payload: &[u8]) -> Vec<u8> {
+        let mut segment = vec![];
+        segment.extend_from_slice(&source_port.to_be_bytes());
+        segment.extend_from_slice(&destination_port.to_be_bytes());
+        segment.extend_from_slice(&(8 + payload.len() as u16).to_be_bytes());
+        segment.extend_from_slice(&[0, 0]);
+        segment.extend_from_slice(payload);
+        segment
+    }
+
+    fn ipv4_packet(source: [u8; 4], segment: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0x45, 0x00];
+        packet.extend_from_slice(&(20 + segment.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&[0, 0, 0x40, 0x00, 64, 17, 0, 0]);
+        packet.extend_from_slice(&source);
+        packet.extend_from_slice(&[127, 0, 0, 1]);
+        packet.extend_from_slice(segment);
+        packet
+    }
+
+    fn ipv6_packet(source: [u8; 16], segment: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0x60, 0, 0, 0];
+        packet.extend_from_slice(&(segment.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&[17, 64]);
+        packet.extend_from_slice(&source);
+        packet.extend_from_slice(&[0; 15]);
+        packet.push(1);
+        packet.extend_from_slice(segment);
+        packet
+    }
+
+    /// Ensures classic little-endian pcap with Ethernet frames is read and filtered.
+    #[test]
+    fn test_pcap_ethernet() {
+        let mut file = vec![];
+        file.extend_from_slice(&0xA1B2C3D4u32.to_le_bytes());
+        file.extend_from_slice(&[2, 0, 4, 0]);
+        file.extend_from_slice(&[0; 8]);
+        file.extend_from_slice(&65535u32.to_le_bytes());
+        file.extend_from_slice(&1u32.to_le_bytes());
+
+        for (seconds, port, value) in [(10u32, 9001u16, 0.5f32), (11, 9000, 1.0), (12, 9001, -1.0)]
+        {
+            let mut frame = vec![0; 12];
+            frame.extend_from_slice(&[0x08, 0x00]);
+            frame.extend_from_slice(&ipv4_packet(
+                [192, 168, 0, 2],
+                &udp_segment(50000, port, &osc_payload(value)),
+            ));
+            file.extend_from_slice(&seconds.to_le_bytes());
+            file.extend_from_slice(&250_000u32.to_le_bytes());
+            file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            file.extend_from_slice(&frame);
+        }
+
+        let reader = PcapReader::new(&file[..])
+            .expect("Should success")
+            .port(9001);
+        let packets = reader
+            .packets()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Should success");
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].0, UNIX_EPOCH + Duration::from_millis(10_250));
+        assert_eq!(
+            packets[0].1,
+            "192.168.0.2:50000".parse().expect("Should valid")
+        );
+        match &packets[1].2 {
+            Packet::Message(m) => assert_eq!(m.arguments(), &[Value::Float32(-1.0)]),
+            Packet::Bundle(_) => panic!("Should be a message"),
+        }
+    }
+
+    /// Ensures big-endian pcapng with loopback IPv6 frames and custom resolution is read.
+    #[test]
+    fn test_pcapng_loopback() {
+        let mut file = vec![];
+        // Section Header Block
+        file.extend_from_slice(&0x0A0D0D0Au32.to_be_bytes());
+        file.extend_from_slice(&28u32.to_be_bytes());
+        file.extend_from_slice(&0x1A2B3C4Du32.to_be_bytes());
+        file.extend_from_slice(&[0, 1, 0, 0]);
+        file.extend_from_slice(&[0xFF; 8]);
+        file.extend_from_slice(&28u32.to_be_bytes());
+
+        // Interface Description Block with if_tsresol = 10^-3
+        file.extend_from_slice(&1u32.to_be_bytes());
+        file.extend_from_slice(&28u32.to_be_bytes());
+        file.extend_from_slice(&0u16.to_be_bytes());
+        file.extend_from_slice(&[0, 0]);
+        file.extend_from_slice(&0u32.to_be_bytes());
+        file.extend_from_slice(&[0, 9, 0, 1, 3, 0, 0, 0]);
+        file.extend_from_slice(&28u32.to_be_bytes());
+
+        // Enhanced Packet Block
+        let mut frame = 30u32.to_le_bytes().to_vec();
+        frame.extend_from_slice(&ipv6_packet(
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+            &udp_segment(40000, 9001, &osc_payload(0.25)),
+        ));
+        let padded_length = (frame.len() + 3) & !3;
+        let total_length = 32 + padded_length as u32;
+        file.extend_from_slice(&6u32.to_be_bytes());
+        file.extend_from_slice(&total_length.to_be_bytes());
+        file.extend_from_slice(&0u32.to_be_bytes());
+        file.extend_from_slice(&0u32.to_be_bytes());
+        file.extend_from_slice(&1_500u32.to_be_bytes());
+        file.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+        file.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+        file.extend_from_slice(&frame);
+        file.resize(file.len() + padded_length - frame.len(), 0);
+        file.extend_from_slice(&total_length.to_be_bytes());
+
+        let records = PcapReader::new(&file[..])
+            .expect("Should success")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Should success");
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0].timestamp,
+            UNIX_EPOCH + Duration::from_millis(1_500)
+        );
+        assert_eq!(
+            records[0].source,
+            "[::1]:40000".parse().expect("Should valid")
+        );
+        assert_eq!(records[0].bytes, osc_payload(0.25));
+    }
+
+    /// Ensures non-capture data is rejected.
+    #[test]
+    fn test_pcap_invalid() {
+        assert!(PcapReader::new(&b"not a pcap file"[..]).is_err());
+    }
+
+    /// Ensures huge lengths in truncated files are rejected without allocating them.
+    #[test]
+    fn test_pcap_huge_length() {
+        let mut file = vec![];
+        file.extend_from_slice(&0xA1B2C3D4u32.to_le_bytes());
+        file.extend_from_slice(&[2, 0, 4, 0]);
+        file.extend_from_slice(&[0; 8]);
+        file.extend_from_slice(&65535u32.to_le_bytes());
+        file.extend_from_slice(&1u32.to_le_bytes());
+        file.extend_from_slice(&[0; 8]);
+        file.extend_from_slice(&u32::MAX.to_le_bytes());
+        file.extend_from_slice(&u32::MAX.to_le_bytes());
+        file.extend_from_slice(&[0; 16]);
+        let mut reader = PcapReader::new(&file[..]).expect("Should success");
+        assert!(matches!(
+            reader.next(),
+            Some(Err(CaptureError::InvalidRecord))
+        ));
+
+        let mut file = vec![];
+        file.extend_from_slice(&0x0A0D0D0Au32.to_be_bytes());
+        file.extend_from_slice(&28u32.to_be_bytes());
+        file.extend_from_slice(&0x1A2B3C4Du32.to_be_bytes());
+        file.extend_from_slice(&[0, 1, 0, 0]);
+        file.extend_from_slice(&[0xFF; 8]);
+        file.extend_from_slice(&28u32.to_be_bytes());
+        file.extend_from_slice(&6u32.to_be_bytes());
+        file.extend_from_slice(&0xFFFFFFFCu32.to_be_bytes());
+        file.extend_from_slice(&[0; 16]);
+        let mut reader = PcapReader::new(&file[..]).expect("Should success");
+        assert!(matches!(
+            reader.next(),
+            Some(Err(CaptureError::InvalidRecord))
+        ));
+    }
+
+    /// Ensures timestamps beyond `SystemTime` range are rejected.
+    #[test]
+    fn test_pcapng_huge_timestamp() {
+        let mut file = vec![];
+        file.extend_from_slice(&0x0A0D0D0Au32.to_be_bytes());
+        file.extend_from_slice(&28u32.to_be_bytes());
+        file.extend_from_slice(&0x1A2B3C4Du32.to_be_bytes());
+        file.extend_from_slice(&[0, 1, 0, 0]);
+        file.extend_from_slice(&[0xFF; 8]);
+        file.extend_from_slice(&28u32.to_be_bytes());
+
+        // Interface Description Block with if_tsresol = 10^0
+        file.extend_from_slice(&1u32.to_be_bytes());
+        file.extend_from_slice(&28u32.to_be_bytes());
+        file.extend_from_slice(&0u16.to_be_bytes());
+        file.extend_from_slice(&[0, 0]);
+        file.extend_from_slice(&0u32.to_be_bytes());
+        file.extend_from_slice(&[0, 9, 0, 1, 0, 0, 0, 0]);
+        file.extend_from_slice(&28u32.to_be_bytes());
+
+        // Enhanced Packet Block without frame
+        file.extend_from_slice(&6u32.to_be_bytes());
+        file.extend_from_slice(&32u32.to_be_bytes());
+        file.extend_from_slice(&0u32.to_be_bytes());
+        file.extend_from_slice(&[0xFF; 8]);
+        file.extend_from_slice(&0u32.to_be_bytes());
+        file.extend_from_slice(&0u32.to_be_bytes());
+        file.extend_from_slice(&32u32.to_be_bytes());
+
+        let mut reader = PcapReader::new(&file[..]).expect("Should success");
+        assert!(matches!(
+            reader.next(),
+            Some(Err(CaptureError::InvalidRecord))
+        ));
+    }
+
+    /// Ensures frames are dispatched by EtherType and broken IPv4 headers are skipped.
+    #[test]
+    fn test_pcap_ethertype() {
+        let mut file = vec![];
+        file.extend_from_slice(&0xA1B2C3D4u32.to_le_bytes());
+        file.extend_from_slice(&[2, 0, 4, 0]);
+        file.extend_from_slice(&[0; 8]);
+        file.extend_from_slice(&65535u32.to_le_bytes());
+        file.extend_from_slice(&1u32.to_le_bytes());
+
+        let packet = ipv4_packet(
+            [192, 168, 0, 2],
+            &udp_segment(50000, 9001, &osc_payload(0.5)),
+        );
+        let mut short_header = packet.clone();
+        short_header[0] = 0x44;
+        for (ethertype, ip_packet) in [
+            ([0x08, 0x06], &packet),
+            ([0x08, 0x00], &short_header),
+            ([0x08, 0x00], &packet),
+        ] {
+            let mut frame = vec![0; 12];
+            frame.extend_from_slice(&ethertype);
+            frame.extend_from_slice(ip_packet);
+            file.extend_from_slice(&[0; 8]);
+            file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            file.extend_from_slice(&frame);
+        }
+
+        let records = PcapReader::new(&file[..])
+            .expect("Should success")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Should success");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].bytes, osc_payload(0.5));
+    }
+}
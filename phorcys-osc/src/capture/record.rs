@@ -0,0 +1,362 @@
+//! Capture file reader and writer.
+//!
+//! A capture file consists of a header and records following it.
+//! All integers are stored in big endian.
+//!
+//! | Part   | Content                                                              |
+//! |:-------|:---------------------------------------------------------------------|
+//! | Header | `CAPTURE_MAGIC` (8 bytes), version (`u32`)                           |
+//! | Record | timestamp in microseconds since UNIX epoch (`u64`)                   |
+//! |        | address family (`u8`, 4 or 6), IP address (4 or 16 bytes), port (`u16`) |
+//! |        | packet length (`u32`), packet bytes                                  |
+
+use crate::{
+    capture::{CaptureError, CaptureResult},
+    packet::Packet,
+};
+
+use std::{
+    io::{ErrorKind as IoErrorKind, Read, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Magic bytes of capture file.
+pub const CAPTURE_MAGIC: &[u8; 8] = b"PHORCAP\0";
+
+/// Current version of capture file format.
+pub const CAPTURE_VERSION: u32 = 1;
+
+/// Represents a captured OSC packet.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Record {
+    /// Time when the packet arrived.
+    pub timestamp: SystemTime,
+
+    /// Socket address which sent the packet.
+    pub source: SocketAddr,
+
+    /// Raw packet bytes.
+    pub bytes: Box<[u8]>,
+}
+
+impl Record {
+    /// Creates a record with current time.
+    pub fn now(source: SocketAddr, bytes: &[u8]) -> Record {
+        Record {
+            timestamp: SystemTime::now(),
+            source,
+            bytes: bytes.into(),
+        }
+    }
+
+    /// Deserializes recorded bytes into OSC Packet.
+    pub fn packet(&self) -> CaptureResult<Packet> {
+        Ok(Packet::deserialize(&self.bytes)?)
+    }
+}
+
+/// Writes capture file into `W`.
+#[derive(Debug)]
+pub struct CaptureWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> CaptureWriter<W> {
+    /// Creates a writer and writes the header.
+    pub fn new(mut writer: W) -> CaptureResult<CaptureWriter<W>> {
+        writer.write_all(CAPTURE_MAGIC)?;
+        writer.write_all(&CAPTURE_VERSION.to_be_bytes())?;
+        Ok(CaptureWriter { writer })
+    }
+
+    /// Writes a record.
+    pub fn write_record(&mut self, record: &Record) -> CaptureResult<()> {
+        if record.bytes.len() > u32::MAX as usize {
+            return Err(CaptureError::InvalidRecord);
+        }
+        let timestamp = record
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| CaptureError::InvalidRecord)?;
+        let timestamp =
+            u64::try_from(timestamp.as_micros()).map_err(|_| CaptureError::InvalidRecord)?;
+
+        self.writer.write_all(&timestamp.to_be_bytes())?;
+        match record.source.ip() {
+            IpAddr::V4(ip) => {
+                self.writer.write_all(&[4])?;
+                self.writer.write_all(&ip.octets())?;
+            }
+            IpAddr::V6(ip) => {
+                self.writer.write_all(&[6])?;
+                self.writer.write_all(&ip.octets())?;
+            }
+        }
+        self.writer.write_all(&record.source.port().to_be_bytes())?;
+        self.writer
+            .write_all(&(record.bytes.len() as u32).to_be_bytes())?;
+        self.writer.write_all(&record.bytes)?;
+        Ok(())
+    }
+
+    /// Writes a packet received now.
+    pub fn write_packet(&mut self, source: SocketAddr, bytes: &[u8]) -> CaptureResult<()> {
+        self.write_record(&Record::now(source, bytes))
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> CaptureResult<()> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// Consumes itself and returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads capture file from `R`.
+/// Records are yielded as an iterator.
+#[derive(Debug)]
+pub struct CaptureReader<R> {
+    reader: R,
+    version: u32,
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Creates a reader and validates the header.
+    pub fn new(mut reader: R) -> CaptureResult<CaptureReader<R>> {
+        let mut header = [0; 12];
+        reader.read_exact(&mut header).map_err(|e| match e.kind() {
+            IoErrorKind::UnexpectedEof => CaptureError::InvalidHeader,
+            _ => e.into(),
+        })?;
+        let (magic, version) = header.split_at(8);
+        if magic != CAPTURE_MAGIC {
+            return Err(CaptureError::InvalidHeader);
+        }
+
+        let version: [u8; 4] = version.try_into().expect("Should be 4 bytes");
+        let version = u32::from_be_bytes(version);
+        if version != CAPTURE_VERSION {
+            return Err(CaptureError::UnsupportedVersion(version));
+        }
+
+        Ok(CaptureReader { reader, version })
+    }
+
+    /// Returns format version of this capture.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Reads the next record.
+    /// Returns `None` if the stream reached its end.
+    pub fn read_record(&mut self) -> CaptureResult<Option<Record>> {
+        let mut timestamp = [0; 8];
+        if !self.read_exact_or_eof(&mut timestamp)? {
+            return Ok(None);
+        }
+        let timestamp = UNIX_EPOCH
+            .checked_add(Duration::from_micros(u64::from_be_bytes(timestamp)))
+            .ok_or(CaptureError::InvalidRecord)?;
+
+        let ip = match self.read_array::<1>()? {
+            [4] => IpAddr::V4(Ipv4Addr::from(self.read_array::<4>()?)),
+            [6] => IpAddr::V6(Ipv6Addr::from(self.read_array::<16>()?)),
+            _ => return Err(CaptureError::InvalidRecord),
+        };
+        let port = u16::from_be_bytes(self.read_array()?);
+        let length = u32::from_be_bytes(self.read_array()?) as usize;
+
+        let bytes = self.read_vec(length)?;
+
+        Ok(Some(Record {
+            timestamp,
+            source: SocketAddr::new(ip, port),
+            bytes: bytes.into_boxed_slice(),
+        }))
+    }
+
+    /// Consumes itself and returns an iterator of deserialized packets.
+    pub fn packets(self) -> impl Iterator<Item = CaptureResult<(SystemTime, SocketAddr, Packet)>> {
+        self.map(|record| {
+            let record = record?;
+            let packet = record.packet()?;
+            Ok((record.timestamp, record.source, packet))
+        })
+    }
+
+    /// Fills `buffer`, but returns `false` when the stream ends before any byte is read.
+    fn read_exact_or_eof(&mut self, buffer: &mut [u8]) -> CaptureResult<bool> {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match self.reader.read(&mut buffer[filled..]) {
+                Ok(0) if filled == 0 => return Ok(false),
+                Ok(0) => return Err(CaptureError::InvalidRecord),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == IoErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(true)
+    }
+
+    /// Reads fixed-length bytes inside a record.
+    fn read_array<const N: usize>(&mut self) -> CaptureResult<[u8; N]> {
+        let mut buffer = [0; N];
+        self.reader
+            .read_exact(&mut buffer)
+            .map_err(|e| match e.kind() {
+                IoErrorKind::UnexpectedEof => CaptureError::InvalidRecord,
+                _ => e.into(),
+            })?;
+        Ok(buffer)
+    }
+
+    /// Reads variable-length bytes inside a record.
+    /// The buffer grows as bytes arrive, so that broken length fields do not cause huge allocation.
+    fn read_vec(&mut self, length: usize) -> CaptureResult<Vec<u8>> {
+        let mut buffer = vec![];
+        self.reader
+            .by_ref()
+            .take(length as u64)
+            .read_to_end(&mut buffer)?;
+        if buffer.len() < length {
+            return Err(CaptureError::InvalidRecord);
+        }
+        Ok(buffer)
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = CaptureResult<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CaptureReader, CaptureWriter, Record, CAPTURE_MAGIC};
+    use crate::{capture::CaptureError, data::Value, packet::MessageBuilder, packet::Packet};
+
+    use std::{
+        io::{Error as IoError, Read, Result as IoResult},
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _buffer: &mut [u8]) -> IoResult<usize> {
+            Err(IoError::other("Broken stream"))
+        }
+    }
+
+    fn message_bytes(value: i32) -> Box<[u8]> {
+        MessageBuilder::new("/avatar/parameters/Foo")
+            .expect("Should valid")
+            .push_argument(Value::Int32(value))
+            .build()
+            .serialize()
+    }
+
+    /// Ensures records can be written and read back.
+    #[test]
+    fn test_capture_roundtrip() {
+        let records = vec![
+            Record {
+                timestamp: UNIX_EPOCH + Duration::from_micros(1_000_000),
+                source: "127.0.0.1:9001".parse().expect("Should valid"),
+                bytes: message_bytes(1),
+            },
+            Record {
+                timestamp: UNIX_EPOCH + Duration::from_micros(1_250_000),
+                source: "[::1]:9001".parse().expect("Should valid"),
+                bytes: message_bytes(2),
+            },
+        ];
+
+        let mut writer = CaptureWriter::new(vec![]).expect("Should success");
+        for record in &records {
+            writer.write_record(record).expect("Should success");
+        }
+        let bytes = writer.into_inner();
+        assert_eq!(&bytes[..8], CAPTURE_MAGIC);
+
+        let reader = CaptureReader::new(&bytes[..]).expect("Should success");
+        let read_records = reader
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Should success");
+        assert_eq!(read_records, records);
+
+        let reader = CaptureReader::new(&bytes[..]).expect("Should success");
+        let packets = reader
+            .packets()
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Should success");
+        match &packets[1].2 {
+            Packet::Message(m) => assert_eq!(m.arguments(), &[Value::Int32(2)]),
+            Packet::Bundle(_) => panic!("Should be a message"),
+        }
+    }
+
+    /// Ensures broken headers and truncated records are rejected.
+    #[test]
+    fn test_capture_invalid() {
+        assert!(matches!(
+            CaptureReader::new(&b"PHORCAP"[..]),
+            Err(CaptureError::InvalidHeader)
+        ));
+        assert!(matches!(
+            CaptureReader::new(&b"PHORCAP\0\x00\x00"[..]),
+            Err(CaptureError::InvalidHeader)
+        ));
+        assert!(matches!(
+            CaptureReader::new(&b"PHORCAP\0\x00\x00\x00\x02"[..]),
+            Err(CaptureError::UnsupportedVersion(2))
+        ));
+
+        let mut writer = CaptureWriter::new(vec![]).expect("Should success");
+        let far_future = Record {
+            timestamp: UNIX_EPOCH + Duration::from_secs(u64::MAX / 1_000_000 + 1),
+            source: "127.0.0.1:9001".parse().expect("Should valid"),
+            bytes: message_bytes(1),
+        };
+        assert!(matches!(
+            writer.write_record(&far_future),
+            Err(CaptureError::InvalidRecord)
+        ));
+        writer
+            .write_packet(
+                "127.0.0.1:9001".parse().expect("Should valid"),
+                &message_bytes(1),
+            )
+            .expect("Should success");
+        let bytes = writer.into_inner();
+        let mut reader = CaptureReader::new(&bytes[..bytes.len() - 2]).expect("Should success");
+        assert!(matches!(
+            reader.read_record(),
+            Err(CaptureError::InvalidRecord)
+        ));
+
+        // Huge length in a truncated record is not allocated.
+        let mut bytes = b"PHORCAP\0\x00\x00\x00\x01".to_vec();
+        bytes.extend_from_slice(&[0; 8]);
+        bytes.extend_from_slice(&[4, 127, 0, 0, 1, 0x23, 0x29]);
+        bytes.extend_from_slice(&u32::MAX.to_be_bytes());
+        let mut reader = CaptureReader::new(&bytes[..]).expect("Should success");
+        assert!(matches!(
+            reader.read_record(),
+            Err(CaptureError::InvalidRecord)
+        ));
+
+        // I/O errors while reading packet bytes are not regarded as broken records.
+        let reader = (&bytes[..]).chain(FailingReader);
+        let mut reader = CaptureReader::new(reader).expect("Should success");
+        assert!(matches!(reader.read_record(), Err(CaptureError::Io(_))));
+    }
+}
@@ -0,0 +1,145 @@
+//! Replays captured packets.
+
+use crate::capture::{CaptureError, CaptureResult, Record};
+
+use std::{
+    net::{ToSocketAddrs, UdpSocket},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+/// Re-sends captured records honoring their original timing.
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    records: Vec<Record>,
+    speed: f64,
+    looping: bool,
+}
+
+impl Replayer {
+    /// Creates a replayer from records.
+    /// Records are sent in given order.
+    pub fn new(records: impl Into<Vec<Record>>) -> Replayer {
+        Replayer {
+            records: records.into(),
+            speed: 1.0,
+            looping: false,
+        }
+    }
+
+    /// Returns records reference.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Sets speed scale. `2.0` replays twice as fast as recorded.
+    /// Fails if `speed` is not a positive finite number.
+    pub fn speed(mut self, speed: f64) -> CaptureResult<Replayer> {
+        if !speed.is_finite() || speed <= 0.0 {
+            return Err(CaptureError::InvalidSpeed(speed));
+        }
+        self.speed = speed;
+        Ok(self)
+    }
+
+    /// Sets whether replaying is repeated endlessly.
+    pub fn looping(mut self, looping: bool) -> Replayer {
+        self.looping = looping;
+        self
+    }
+
+    /// Sends all records to `target` through `socket`.
+    /// If looping is enabled, this function never returns unless an error occurs.
+    pub fn replay_to(&self, socket: &UdpSocket, target: impl ToSocketAddrs) -> CaptureResult<()> {
+        let targets: Vec<_> = target.to_socket_addrs()?.collect();
+        loop {
+            self.replay_once(socket, &targets[..])?;
+            if !self.looping || self.records.is_empty() {
+                break Ok(());
+            }
+        }
+    }
+
+    /// Sends all records once.
+    fn replay_once(
+        &self,
+        socket: &UdpSocket,
+        targets: impl ToSocketAddrs + Copy,
+    ) -> CaptureResult<()> {
+        let first_timestamp = match self.records.first() {
+            Some(r) => r.timestamp,
+            None => return Ok(()),
+        };
+        let started_at = Instant::now();
+
+        for record in &self.records {
+            let offset = record
+                .timestamp
+                .duration_since(first_timestamp)
+                .unwrap_or_default();
+            // Too slow speed makes the scaled offset unrepresentable.
+            let offset = Duration::try_from_secs_f64(offset.as_secs_f64() / self.speed)
+                .map_err(|_| CaptureError::InvalidSpeed(self.speed))?;
+            let elapsed = started_at.elapsed();
+            if offset > elapsed {
+                sleep(offset - elapsed);
+            }
+
+            socket.send_to(&record.bytes, targets)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Replayer;
+    use crate::capture::{CaptureError, Record};
+
+    use std::{
+        net::UdpSocket,
+        time::{Duration, Instant, UNIX_EPOCH},
+    };
+
+    /// Ensures records are sent in order with scaled intervals.
+    #[test]
+    fn test_replay_timing() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").expect("Should bind");
+        let sender = UdpSocket::bind("127.0.0.1:0").expect("Should bind");
+        let source = sender.local_addr().expect("Should have address");
+
+        let records: Vec<_> = (0..3u8)
+            .map(|i| Record {
+                timestamp: UNIX_EPOCH + Duration::from_millis(1000 + i as u64 * 200),
+                source,
+                bytes: vec![i; 4].into_boxed_slice(),
+            })
+            .collect();
+
+        let started_at = Instant::now();
+        Replayer::new(records.clone())
+            .speed(4.0)
+            .expect("Should valid")
+            .replay_to(&sender, receiver.local_addr().expect("Should have address"))
+            .expect("Should success");
+        assert!(started_at.elapsed() >= Duration::from_millis(100));
+
+        let mut buffer = [0; 16];
+        for i in 0..3u8 {
+            let (length, _) = receiver.recv_from(&mut buffer).expect("Should receive");
+            assert_eq!(&buffer[..length], &[i; 4]);
+        }
+
+        for speed in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                Replayer::new(records.clone()).speed(speed),
+                Err(CaptureError::InvalidSpeed(_))
+            ));
+        }
+        let result = Replayer::new(records)
+            .speed(1e-300)
+            .expect("Should valid")
+            .replay_to(&sender, receiver.local_addr().expect("Should have address"));
+        assert!(matches!(result, Err(CaptureError::InvalidSpeed(_))));
+    }
+}
@@ -1,9 +1,28 @@
 //! Data types.
 
+use crate::{encode::SliceWriter, error::Result};
+
+use alloc::{string::String, vec::Vec};
+use core::{
+    cmp::Ordering,
+    fmt::{Display, Formatter, Result as FmtResult},
+    hash::{Hash, Hasher},
+};
+
 /// Represents a time tag.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct TimeTag(pub u64);
 
+impl TimeTag {
+    /// Special time tag which means "immediately".
+    pub const IMMEDIATELY: TimeTag = TimeTag(1);
+
+    /// Whether this time tag means "immediately".
+    pub fn is_immediate(self) -> bool {
+        self == TimeTag::IMMEDIATELY
+    }
+}
+
 /// Represents single data in OSC packet.
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum Value {
@@ -96,23 +115,17 @@ impl Value {
             Value::Color(c) => buffer.extend_from_slice(&c),
             Value::MidiMessage(m) => buffer.extend_from_slice(&m),
             Value::TimeTag(t) => buffer.extend_from_slice(&t.0.to_be_bytes()),
-            Value::String(s) => {
-                let mut aligned = s.into_bytes();
-                aligned.push(0);
-                Value::align_bytes(&mut aligned);
-                buffer.extend_from_slice(&aligned);
+            Value::String(s) | Value::Alternative(s) => {
+                buffer.extend_from_slice(s.as_bytes());
+                buffer.resize(
+                    buffer.len() + Value::aligned_length(s.len() + 1) - s.len(),
+                    0,
+                );
             }
-            Value::Alternative(s) => {
-                let mut aligned = s.into_bytes();
-                aligned.push(0);
-                Value::align_bytes(&mut aligned);
-                buffer.extend_from_slice(&aligned);
-            }
-            Value::Blob(mut b) if b.len() < i32::MAX as usize => {
-                let length_bytes = (b.len() as i32).to_be_bytes();
-                Value::align_bytes(&mut b);
-                buffer.extend_from_slice(&length_bytes);
+            Value::Blob(b) if b.len() < i32::MAX as usize => {
+                buffer.extend_from_slice(&(b.len() as i32).to_be_bytes());
                 buffer.extend_from_slice(&b);
+                buffer.resize(buffer.len() + Value::aligned_length(b.len()) - b.len(), 0);
             }
             Value::Array(values) => {
                 for v in values {
@@ -125,10 +138,97 @@ impl Value {
 }
 
 impl Value {
+    /// Returns the length of argument data written by `write_aligned_into`.
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            Value::Nil | Value::Infinitum | Value::Boolean(_) => 0,
+            Value::Character(_)
+            | Value::Int32(_)
+            | Value::Float32(_)
+            | Value::Color(_)
+            | Value::MidiMessage(_) => 4,
+            Value::Int64(_) | Value::Float64(_) | Value::TimeTag(_) => 8,
+            Value::String(s) | Value::Alternative(s) => Value::aligned_length(s.len() + 1),
+            Value::Blob(b) => 4 + Value::aligned_length(b.len()),
+            Value::Array(values) => values.iter().map(Value::encoded_len).sum(),
+        }
+    }
+
+    /// Returns the number of type tag characters of this value.
+    pub fn type_tag_len(&self) -> usize {
+        match self {
+            Value::Array(values) => 2 + values.iter().map(Value::type_tag_len).sum::<usize>(),
+            _ => 1,
+        }
+    }
+
+    /// Writes argument data into `buffer` with aligning, without allocation.
+    /// Returns the written length.
+    pub fn encode_into(&self, buffer: &mut [u8]) -> Result<usize> {
+        let mut writer = SliceWriter::new(buffer);
+        self.encode_with(&mut writer)?;
+        Ok(writer.position())
+    }
+
+    /// Writes type tag of this value.
+    pub(crate) fn encode_type_tag_with(&self, writer: &mut SliceWriter<'_>) -> Result<()> {
+        match self {
+            Value::Array(values) => {
+                writer.write(b"[")?;
+                for v in values {
+                    v.encode_type_tag_with(writer)?;
+                }
+                writer.write(b"]")
+            }
+            Value::Nil => writer.write(b"N"),
+            Value::Infinitum => writer.write(b"I"),
+            Value::Boolean(true) => writer.write(b"T"),
+            Value::Boolean(false) => writer.write(b"F"),
+            Value::Character(_) => writer.write(b"c"),
+            Value::Int32(_) => writer.write(b"i"),
+            Value::Int64(_) => writer.write(b"h"),
+            Value::Float32(_) => writer.write(b"f"),
+            Value::Float64(_) => writer.write(b"d"),
+            Value::Color(_) => writer.write(b"r"),
+            Value::MidiMessage(_) => writer.write(b"m"),
+            Value::TimeTag(_) => writer.write(b"t"),
+            Value::String(_) => writer.write(b"s"),
+            Value::Alternative(_) => writer.write(b"S"),
+            Value::Blob(_) => writer.write(b"b"),
+        }
+    }
+
+    /// Writes argument data with aligning.
+    pub(crate) fn encode_with(&self, writer: &mut SliceWriter<'_>) -> Result<()> {
+        match self {
+            Value::Nil | Value::Infinitum | Value::Boolean(_) => Ok(()),
+            Value::Character(c) => writer.write(&(*c as u32).to_be_bytes()),
+            Value::Int32(x) => writer.write(&x.to_be_bytes()),
+            Value::Int64(x) => writer.write(&x.to_be_bytes()),
+            Value::Float32(x) => writer.write(&x.to_be_bytes()),
+            Value::Float64(x) => writer.write(&x.to_be_bytes()),
+            Value::Color(c) => writer.write(c),
+            Value::MidiMessage(m) => writer.write(m),
+            Value::TimeTag(t) => writer.write(&t.0.to_be_bytes()),
+            Value::String(s) | Value::Alternative(s) => writer.write_string(s.as_bytes()),
+            Value::Blob(b) => {
+                writer.write(&(b.len() as i32).to_be_bytes())?;
+                writer.write(b)?;
+                writer.pad()
+            }
+            Value::Array(values) => {
+                for v in values {
+                    v.encode_with(writer)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
     /// Align the `data` length to multiple of 4.
     pub fn aligned_length(original_length: usize) -> usize {
         let mut words = original_length >> 2;
-        if original_length % 4 != 0 {
+        if !original_length.is_multiple_of(4) {
             words += 1;
         }
         words << 2
@@ -192,9 +292,242 @@ impl From<&str> for Value {
     }
 }
 
+impl Value {
+    /// Returns the value as `f32`.
+    /// Integers and floats are cast, and booleans become `1.0` or `0.0` as VRChat does.
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            Value::Float32(x) => Some(*x),
+            Value::Float64(x) => Some(*x as f32),
+            Value::Int32(x) => Some(*x as f32),
+            Value::Int64(x) => Some(*x as f32),
+            Value::Boolean(b) => Some(if *b { 1.0 } else { 0.0 }),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as `i32`.
+    /// Floats are truncated toward zero and saturated, and booleans become `1` or `0` as VRChat does.
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            Value::Int32(x) => Some(*x),
+            Value::Int64(x) => Some((*x).clamp(i32::MIN as i64, i32::MAX as i64) as i32),
+            Value::Float32(x) => Some(*x as i32),
+            Value::Float64(x) => Some(*x as i32),
+            Value::Boolean(b) => Some(*b as i32),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as `bool`.
+    /// Numbers are `true` when they are not zero, as VRChat does.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(b) => Some(*b),
+            Value::Int32(x) => Some(*x != 0),
+            Value::Int64(x) => Some(*x != 0),
+            Value::Float32(x) => Some(*x != 0.0),
+            Value::Float64(x) => Some(*x != 0.0),
+            _ => None,
+        }
+    }
+
+    /// Returns the string of `s` or `S`.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) | Value::Alternative(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Whether this value is regarded as true.
+    /// Numbers follow `as_bool`, `Nil` is false, `Infinitum` is true,
+    /// and strings, blobs and arrays are true when they are not empty.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Nil => false,
+            Value::String(s) | Value::Alternative(s) => !s.is_empty(),
+            Value::Blob(b) => !b.is_empty(),
+            Value::Array(values) => !values.is_empty(),
+            otherwise => otherwise.as_bool().unwrap_or(true),
+        }
+    }
+
+    /// Returns the index of the variant, used for total ordering.
+    fn variant_index(&self) -> u8 {
+        match self {
+            Value::Nil => 0,
+            Value::Infinitum => 1,
+            Value::Boolean(_) => 2,
+            Value::Character(_) => 3,
+            Value::Int32(_) => 4,
+            Value::Int64(_) => 5,
+            Value::Float32(_) => 6,
+            Value::Float64(_) => 7,
+            Value::Color(_) => 8,
+            Value::MidiMessage(_) => 9,
+            Value::TimeTag(_) => 10,
+            Value::String(_) => 11,
+            Value::Alternative(_) => 12,
+            Value::Blob(_) => 13,
+            Value::Array(_) => 14,
+        }
+    }
+}
+
+/// Formats in OSC text notation.
+/// Strings are quoted, floats always have a fraction part, and arrays are enclosed with brackets.
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Value::Nil => write!(f, "Nil"),
+            Value::Infinitum => write!(f, "Infinitum"),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::Character(c) => write!(f, "'{}'", c.escape_debug()),
+            Value::Int32(x) => write!(f, "{}", x),
+            Value::Int64(x) => write!(f, "{}", x),
+            Value::Float32(x) => write!(f, "{:?}", x),
+            Value::Float64(x) => write!(f, "{:?}", x),
+            Value::Color([r, g, b, a]) => write!(f, "#{:02X}{:02X}{:02X}{:02X}", r, g, b, a),
+            Value::MidiMessage([p, s, d1, d2]) => {
+                write!(f, "MIDI[{:02X} {:02X} {:02X} {:02X}]", p, s, d1, d2)
+            }
+            Value::TimeTag(t) => write!(f, "TimeTag({:#018X})", t.0),
+            Value::String(s) | Value::Alternative(s) => write!(f, "\"{}\"", s.escape_debug()),
+            Value::Blob(b) => {
+                write!(f, "Blob[")?;
+                for (i, byte) in b.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{:02X}", byte)?;
+                }
+                write!(f, "]")
+            }
+            Value::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    value.fmt(f)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// Wraps `Value` with total order, `Eq` and `Hash`.
+/// Floats are compared by their bits, so `NaN` equals itself and `0.0` differs from `-0.0`.
+#[derive(Debug, Clone)]
+pub struct CanonicalValue(pub Value);
+
+impl CanonicalValue {
+    /// Consumes itself and returns inner `Value`.
+    pub fn into_inner(self) -> Value {
+        self.0
+    }
+}
+
+impl From<Value> for CanonicalValue {
+    fn from(value: Value) -> Self {
+        CanonicalValue(value)
+    }
+}
+
+impl From<CanonicalValue> for Value {
+    fn from(value: CanonicalValue) -> Self {
+        value.0
+    }
+}
+
+impl PartialEq for CanonicalValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for CanonicalValue {}
+
+impl PartialOrd for CanonicalValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CanonicalValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        canonical_cmp(&self.0, &other.0)
+    }
+}
+
+impl Hash for CanonicalValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        canonical_hash(&self.0, state);
+    }
+}
+
+/// Compares values in total order.
+fn canonical_cmp(lhs: &Value, rhs: &Value) -> Ordering {
+    match (lhs, rhs) {
+        (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
+        (Value::Character(a), Value::Character(b)) => a.cmp(b),
+        (Value::Int32(a), Value::Int32(b)) => a.cmp(b),
+        (Value::Int64(a), Value::Int64(b)) => a.cmp(b),
+        (Value::Float32(a), Value::Float32(b)) => a.total_cmp(b),
+        (Value::Float64(a), Value::Float64(b)) => a.total_cmp(b),
+        (Value::Color(a), Value::Color(b)) => a.cmp(b),
+        (Value::MidiMessage(a), Value::MidiMessage(b)) => a.cmp(b),
+        (Value::TimeTag(a), Value::TimeTag(b)) => a.cmp(b),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Alternative(a), Value::Alternative(b)) => a.cmp(b),
+        (Value::Blob(a), Value::Blob(b)) => a.cmp(b),
+        (Value::Array(a), Value::Array(b)) => {
+            for (x, y) in a.iter().zip(b) {
+                match canonical_cmp(x, y) {
+                    Ordering::Equal => continue,
+                    otherwise => return otherwise,
+                }
+            }
+            a.len().cmp(&b.len())
+        }
+        _ => lhs.variant_index().cmp(&rhs.variant_index()),
+    }
+}
+
+/// Hashes a value consistently with `canonical_cmp`.
+fn canonical_hash<H: Hasher>(value: &Value, state: &mut H) {
+    value.variant_index().hash(state);
+    match value {
+        Value::Nil | Value::Infinitum => (),
+        Value::Boolean(b) => b.hash(state),
+        Value::Character(c) => c.hash(state),
+        Value::Int32(x) => x.hash(state),
+        Value::Int64(x) => x.hash(state),
+        Value::Float32(x) => x.to_bits().hash(state),
+        Value::Float64(x) => x.to_bits().hash(state),
+        Value::Color(c) => c.hash(state),
+        Value::MidiMessage(m) => m.hash(state),
+        Value::TimeTag(t) => t.hash(state),
+        Value::String(s) | Value::Alternative(s) => s.hash(state),
+        Value::Blob(b) => b.hash(state),
+        Value::Array(values) => {
+            values.len().hash(state);
+            for v in values {
+                canonical_hash(v, state);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::Value;
+    use super::{CanonicalValue, Value};
+
+    use alloc::{collections::BTreeSet, string::String, string::ToString, vec};
+    #[cfg(feature = "std")]
+    use std::collections::HashSet;
 
     #[test]
     fn test_value_length_calculation() {
@@ -232,4 +565,65 @@ mod test {
             ]
         );
     }
+
+    /// Ensures values are formatted in OSC text notation.
+    #[test]
+    fn test_value_display() {
+        assert_eq!(Value::Int32(-3).to_string(), "-3");
+        assert_eq!(Value::Float32(1.0).to_string(), "1.0");
+        assert_eq!(Value::Boolean(true).to_string(), "true");
+        assert_eq!(Value::String("a\"b".into()).to_string(), r#""a\"b""#);
+        assert_eq!(
+            Value::Color([0xFF, 0x00, 0x80, 0x01]).to_string(),
+            "#FF008001"
+        );
+        assert_eq!(Value::Blob(vec![0x01, 0xAB]).to_string(), "Blob[01 AB]");
+        assert_eq!(
+            Value::Array(vec![Value::Nil, Value::Character('x')]).to_string(),
+            "[Nil, 'x']"
+        );
+    }
+
+    /// Ensures canonical values can be deduplicated including floats.
+    #[test]
+    fn test_canonical_value() {
+        let values = [
+            Value::Float32(f32::NAN),
+            Value::Float32(f32::NAN),
+            Value::Float32(0.0),
+            Value::Float32(-0.0),
+            Value::Int32(0),
+            Value::Array(vec![Value::Float64(0.5)]),
+            Value::Array(vec![Value::Float64(0.5)]),
+        ];
+        let ordered: BTreeSet<_> = values.iter().cloned().map(CanonicalValue).collect();
+        assert_eq!(ordered.len(), 5);
+        #[cfg(feature = "std")]
+        {
+            let hashed: HashSet<_> = values.into_iter().map(CanonicalValue).collect();
+            assert_eq!(hashed.len(), 5);
+        }
+
+        assert!(CanonicalValue(Value::Nil) < CanonicalValue(Value::Int32(0)));
+        assert!(CanonicalValue(Value::Float32(-0.0)) < CanonicalValue(Value::Float32(0.0)));
+    }
+
+    /// Ensures accessors follow VRChat coercion rules.
+    #[test]
+    fn test_value_accessors() {
+        assert_eq!(Value::Int32(2).as_f32(), Some(2.0));
+        assert_eq!(Value::Boolean(true).as_f32(), Some(1.0));
+        assert_eq!(Value::Float32(1.9).as_i32(), Some(1));
+        assert_eq!(Value::Int64(i64::MAX).as_i32(), Some(i32::MAX));
+        assert_eq!(Value::Float32(0.0).as_bool(), Some(false));
+        assert_eq!(Value::Int32(5).as_bool(), Some(true));
+        assert_eq!(Value::String("x".into()).as_bool(), None);
+        assert_eq!(Value::Alternative("x".into()).as_str(), Some("x"));
+        assert_eq!(Value::Int32(1).as_str(), None);
+
+        assert!(!Value::Nil.is_truthy());
+        assert!(Value::Infinitum.is_truthy());
+        assert!(!Value::String(String::new()).is_truthy());
+        assert!(Value::Float32(0.1).is_truthy());
+    }
 }
@@ -0,0 +1,55 @@
+//! Allocation-free encoding into fixed buffers.
+
+use crate::error::{Error, Result};
+
+/// Writes bytes into a fixed buffer sequentially.
+pub(crate) struct SliceWriter<'a> {
+    buffer: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    /// Creates a writer at the beginning of `buffer`.
+    pub(crate) fn new(buffer: &'a mut [u8]) -> SliceWriter<'a> {
+        SliceWriter {
+            buffer,
+            position: 0,
+        }
+    }
+
+    /// Returns the number of written bytes.
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Writes `bytes`.
+    pub(crate) fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        let end = self.position + bytes.len();
+        if end > self.buffer.len() {
+            return Err(Error::BufferTooSmall);
+        }
+        self.buffer[self.position..end].copy_from_slice(bytes);
+        self.position = end;
+        Ok(())
+    }
+
+    /// Overwrites already written bytes at `position`.
+    pub(crate) fn write_at(&mut self, position: usize, bytes: &[u8]) {
+        self.buffer[position..(position + bytes.len())].copy_from_slice(bytes);
+    }
+
+    /// Writes NUL bytes until the position is aligned to 4.
+    pub(crate) fn pad(&mut self) -> Result<()> {
+        while !self.position.is_multiple_of(4) {
+            self.write(&[0])?;
+        }
+        Ok(())
+    }
+
+    /// Writes `bytes` as OSC-string, with NUL terminator and padding.
+    pub(crate) fn write_string(&mut self, bytes: &[u8]) -> Result<()> {
+        self.write(bytes)?;
+        self.write(&[0])?;
+        self.pad()
+    }
+}
@@ -1,6 +1,9 @@
 //! Error types.
 
-use std::result::Result as StdResult;
+use crate::data::TimeTag;
+
+use alloc::string::String;
+use core::result::Result as StdResult;
 
 use thiserror::Error as ThisError;
 
@@ -36,13 +39,44 @@ pub enum Error {
     #[error("Unknown data type: {0}")]
     UnknownType(u8),
 
+    /// Arrays in type tag or bundles are nested too deeply.
+    #[error("Arrays or bundles nested too deeply")]
+    TooDeep,
+
     /// Shortage in argument data bytes.
     #[error("Not enough argument data")]
     NotEnoughData,
 
+    /// Message arguments do not match the expected signature.
+    #[error("Signature mismatch at {address}: expected {expected}, got {actual}")]
+    SignatureMismatch {
+        /// Address of the message.
+        address: String,
+
+        /// Expected type tags.
+        expected: String,
+
+        /// Actual type tags.
+        actual: String,
+    },
+
+    /// Buffer is too small to encode into.
+    #[error("Buffer too small")]
+    BufferTooSmall,
+
     /// Invalid bundle data.
     #[error("Invalid bundle")]
     InvalidBundle,
+
+    /// Nested bundle has time tag earlier than the enclosing one.
+    #[error("Nested time tag {nested:?} precedes enclosing {enclosing:?}")]
+    InvalidTimeTag {
+        /// Time tag of the enclosing bundle.
+        enclosing: TimeTag,
+
+        /// Time tag of the nested bundle.
+        nested: TimeTag,
+    },
 }
 
 /// Result type shorthand with `crate::Error`.
@@ -0,0 +1,155 @@
+//! `arbitrary::Arbitrary` implementations.
+
+use crate::{
+    address::AddressBuf,
+    data::{TimeTag, Value},
+    generator::{MAX_ADDRESS_PARTS, MAX_DEPTH, MAX_ELEMENTS, MAX_LENGTH, PART_CHARS},
+    packet::{Bundle, Message, MessageBuilder, Packet},
+};
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+impl<'a> Arbitrary<'a> for AddressBuf {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut address = String::new();
+        for _ in 0..u.int_in_range(1..=MAX_ADDRESS_PARTS)? {
+            address.push('/');
+            for _ in 0..u.int_in_range(1..=MAX_LENGTH)? {
+                address.push(*u.choose(PART_CHARS)? as char);
+            }
+        }
+        Ok(AddressBuf::new(&address).expect("Generated address should be valid"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for TimeTag {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(TimeTag(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Value {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_value(u, MAX_DEPTH)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Message {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let address: AddressBuf = u.arbitrary()?;
+        let mut arguments = vec![];
+        for _ in 0..u.int_in_range(0..=MAX_ELEMENTS)? {
+            arguments.push(u.arbitrary()?);
+        }
+        Ok(MessageBuilder::from_address(address)
+            .set_arguments(arguments)
+            .build())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Bundle {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_bundle(u, MAX_DEPTH, TimeTag::default())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Packet {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        arbitrary_packet(u, MAX_DEPTH, TimeTag::default())
+    }
+}
+
+/// Generates a value with arrays nested at most `depth` levels.
+fn arbitrary_value(u: &mut Unstructured<'_>, depth: usize) -> Result<Value> {
+    let variants = if depth == 0 { 14 } else { 15 };
+    let value = match u.int_in_range(0..=(variants - 1))? {
+        0 => Value::Nil,
+        1 => Value::Infinitum,
+        2 => Value::Boolean(u.arbitrary()?),
+        3 => Value::Character(*u.choose(PART_CHARS)? as char),
+        4 => Value::Int32(u.arbitrary()?),
+        5 => Value::Int64(u.arbitrary()?),
+        6 => {
+            let x: f32 = u.arbitrary()?;
+            Value::Float32(if x.is_nan() { 0.0 } else { x })
+        }
+        7 => {
+            let x: f64 = u.arbitrary()?;
+            Value::Float64(if x.is_nan() { 0.0 } else { x })
+        }
+        8 => Value::Color(u.arbitrary()?),
+        9 => Value::MidiMessage(u.arbitrary()?),
+        10 => Value::TimeTag(u.arbitrary()?),
+        11 => Value::String(arbitrary_string(u)?),
+        12 => Value::Alternative(arbitrary_string(u)?),
+        13 => {
+            let length = u.int_in_range(0..=MAX_LENGTH)?;
+            Value::Blob(u.bytes(length)?.to_vec())
+        }
+        _ => {
+            let mut values = vec![];
+            for _ in 0..u.int_in_range(0..=MAX_ELEMENTS)? {
+                values.push(arbitrary_value(u, depth - 1)?);
+            }
+            Value::Array(values)
+        }
+    };
+    Ok(value)
+}
+
+/// Generates a bundle with bundles nested at most `depth` levels.
+/// Time tags are not earlier than `earliest`, so that nested ones are valid.
+fn arbitrary_bundle(u: &mut Unstructured<'_>, depth: usize, earliest: TimeTag) -> Result<Bundle> {
+    let time_tag = TimeTag::arbitrary(u)?.max(earliest);
+    let mut elements = vec![];
+    for _ in 0..u.int_in_range(0..=MAX_ELEMENTS)? {
+        elements.push(arbitrary_packet(u, depth.saturating_sub(1), time_tag)?);
+    }
+    Ok(Bundle::new(time_tag, elements))
+}
+
+/// Generates a packet with bundles nested at most `depth` levels.
+fn arbitrary_packet(u: &mut Unstructured<'_>, depth: usize, earliest: TimeTag) -> Result<Packet> {
+    if depth > 0 && u.arbitrary()? {
+        Ok(Packet::Bundle(arbitrary_bundle(u, depth, earliest)?))
+    } else {
+        Ok(Packet::Message(u.arbitrary()?))
+    }
+}
+
+/// Generates a printable ASCII string without NUL.
+fn arbitrary_string(u: &mut Unstructured<'_>) -> Result<String> {
+    let mut string = String::new();
+    for _ in 0..u.int_in_range(0..=MAX_LENGTH)? {
+        string.push(u.int_in_range(0x20u8..=0x7E)? as char);
+    }
+    Ok(string)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::packet::Packet;
+
+    use arbitrary::{Arbitrary, Unstructured};
+
+    /// Ensures generated packets survive serialization and deserialization.
+    #[test]
+    fn test_arbitrary_roundtrip() {
+        let mut seed = 0x2545_F491_4F6C_DD1Du64;
+        for _ in 0..256 {
+            let data: Vec<u8> = (0..1024)
+                .map(|_| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 7;
+                    seed ^= seed << 17;
+                    seed as u8
+                })
+                .collect();
+            let mut u = Unstructured::new(&data);
+            let packet = Packet::arbitrary(&mut u).expect("Should generate");
+
+            let bytes = packet.clone().serialize();
+            assert_eq!(Packet::deserialize(&bytes), Ok(packet));
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Generators of spec-valid OSC types for fuzzing and property tests.
+//!
+//! `arbitrary` feature implements `arbitrary::Arbitrary`,
+//! and `proptest` feature provides strategies and implements `proptest::arbitrary::Arbitrary`.
+//! Generated floats are never `NaN` so that generated values can be compared after round-trip.
+
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
+
+#[cfg(feature = "proptest")]
+pub mod strategy;
+
+/// Characters available in generated OSC method names.
+pub const PART_CHARS: &[u8] =
+    br#"!"$%&'()+-.0123456789:;<=>@ABCDEFGHIJKLMNOPQRSTUVWXYZ\^_`abcdefghijklmnopqrstuvwxyz|~"#;
+
+/// Maximum number of parts in generated addresses.
+pub const MAX_ADDRESS_PARTS: usize = 4;
+
+/// Maximum length of generated address parts, strings and blobs.
+pub const MAX_LENGTH: usize = 16;
+
+/// Maximum number of arguments or bundle elements.
+pub const MAX_ELEMENTS: usize = 6;
+
+/// Maximum nesting depth of arrays and bundles.
+pub const MAX_DEPTH: usize = 3;
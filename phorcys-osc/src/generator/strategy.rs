@@ -0,0 +1,176 @@
+//! `proptest` strategies.
+
+use crate::{
+    address::AddressBuf,
+    data::{TimeTag, Value},
+    generator::{MAX_ADDRESS_PARTS, MAX_DEPTH, MAX_ELEMENTS, MAX_LENGTH, PART_CHARS},
+    packet::{Bundle, Message, MessageBuilder, Packet},
+};
+
+use proptest::{
+    arbitrary::{any, Arbitrary},
+    collection::vec,
+    num, prop_oneof,
+    sample::select,
+    strategy::{BoxedStrategy, Just, Strategy},
+};
+
+/// Generates valid addresses.
+pub fn address() -> impl Strategy<Value = AddressBuf> {
+    let part = vec(select(PART_CHARS), 1..=MAX_LENGTH)
+        .prop_map(|chars| chars.into_iter().map(char::from).collect::<String>());
+    vec(part, 1..=MAX_ADDRESS_PARTS).prop_map(|parts| {
+        AddressBuf::new(&format!("/{}", parts.join("/")))
+            .expect("Generated address should be valid")
+    })
+}
+
+/// Generates printable ASCII strings without NUL.
+fn string() -> impl Strategy<Value = String> {
+    vec(0x20u8..=0x7E, 0..=MAX_LENGTH).prop_map(|bytes| bytes.into_iter().map(char::from).collect())
+}
+
+/// Generates values except arrays.
+fn scalar_value() -> impl Strategy<Value = Value> {
+    let not_nan_f32 = num::f32::POSITIVE
+        | num::f32::NEGATIVE
+        | num::f32::ZERO
+        | num::f32::SUBNORMAL
+        | num::f32::INFINITE;
+    let not_nan_f64 = num::f64::POSITIVE
+        | num::f64::NEGATIVE
+        | num::f64::ZERO
+        | num::f64::SUBNORMAL
+        | num::f64::INFINITE;
+    prop_oneof![
+        Just(Value::Nil),
+        Just(Value::Infinitum),
+        any::<bool>().prop_map(Value::Boolean),
+        select(PART_CHARS).prop_map(|c| Value::Character(c as char)),
+        any::<i32>().prop_map(Value::Int32),
+        any::<i64>().prop_map(Value::Int64),
+        not_nan_f32.prop_map(Value::Float32),
+        not_nan_f64.prop_map(Value::Float64),
+        any::<[u8; 4]>().prop_map(Value::Color),
+        any::<[u8; 4]>().prop_map(Value::MidiMessage),
+        any::<u64>().prop_map(|t| Value::TimeTag(TimeTag(t))),
+        string().prop_map(Value::String),
+        string().prop_map(Value::Alternative),
+        vec(any::<u8>(), 0..=MAX_LENGTH).prop_map(Value::Blob),
+    ]
+}
+
+/// Generates values with arrays nested at most `MAX_DEPTH` levels.
+pub fn value() -> impl Strategy<Value = Value> {
+    scalar_value().prop_recursive(MAX_DEPTH as u32, 64, MAX_ELEMENTS as u32, |inner| {
+        vec(inner, 0..=MAX_ELEMENTS).prop_map(Value::Array)
+    })
+}
+
+/// Generates messages.
+pub fn message() -> impl Strategy<Value = Message> {
+    (address(), vec(value(), 0..=MAX_ELEMENTS)).prop_map(|(address, arguments)| {
+        MessageBuilder::from_address(address)
+            .set_arguments(arguments)
+            .build()
+    })
+}
+
+/// Generates packets with bundles nested at most `MAX_DEPTH` levels.
+pub fn packet() -> impl Strategy<Value = Packet> {
+    message().prop_map(Packet::Message).prop_recursive(
+        MAX_DEPTH as u32,
+        64,
+        MAX_ELEMENTS as u32,
+        |inner| {
+            (any::<u64>(), vec(inner, 0..=MAX_ELEMENTS))
+                .prop_map(|(time_tag, elements)| Packet::Bundle(valid_bundle(time_tag, elements)))
+        },
+    )
+}
+
+/// Generates bundles.
+pub fn bundle() -> impl Strategy<Value = Bundle> {
+    (any::<u64>(), vec(packet(), 0..=MAX_ELEMENTS))
+        .prop_map(|(time_tag, elements)| valid_bundle(time_tag, elements))
+}
+
+/// Creates a bundle whose time tag is not later than nested ones.
+fn valid_bundle(time_tag: u64, elements: Vec<Packet>) -> Bundle {
+    let earliest_nested = elements
+        .iter()
+        .filter_map(|e| match e {
+            Packet::Bundle(b) if !b.time_tag().is_immediate() => Some(b.time_tag().0),
+            _ => None,
+        })
+        .min()
+        .unwrap_or(u64::MAX);
+    Bundle::new(TimeTag(time_tag.min(earliest_nested)), elements)
+}
+
+impl Arbitrary for AddressBuf {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<AddressBuf>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        address().boxed()
+    }
+}
+
+impl Arbitrary for Value {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Value>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        value().boxed()
+    }
+}
+
+impl Arbitrary for Message {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Message>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        message().boxed()
+    }
+}
+
+impl Arbitrary for Bundle {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Bundle>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        bundle().boxed()
+    }
+}
+
+impl Arbitrary for Packet {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Packet>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        packet().boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{address::AddressBuf, packet::Packet};
+
+    use proptest::{arbitrary::any, prop_assert_eq, proptest};
+
+    proptest! {
+        /// Ensures generated addresses are accepted by validation.
+        #[test]
+        fn test_strategy_address(address in any::<AddressBuf>()) {
+            prop_assert_eq!(AddressBuf::new(address.as_str()), Ok(address.clone()));
+        }
+
+        /// Ensures generated packets survive serialization and deserialization.
+        #[test]
+        fn test_strategy_roundtrip(packet in any::<Packet>()) {
+            let bytes = packet.clone().serialize();
+            prop_assert_eq!(Packet::deserialize(&bytes), Ok(packet));
+        }
+    }
+}
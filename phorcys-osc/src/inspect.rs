@@ -0,0 +1,467 @@
+//! Human-readable inspection of packets for debugging.
+//!
+//! `HexDump` annotates each part of encoded bytes, and `PacketDiff` reports structural differences.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::Range,
+};
+
+use crate::{
+    address::AddressBuf,
+    data::{CanonicalValue, TimeTag, Value},
+    packet::{Bundle, Message, Packet, BUNDLE_HEADER},
+    signature::{TypeTag, TypeTags},
+};
+
+/// Number of bytes in a line of hex dump.
+const BYTES_PER_LINE: usize = 16;
+
+/// Formats a packet as hex dump, with each part of bytes labelled.
+///
+/// ```text
+/// 0000  2F 66 6F 6F 00 00 00 00                          address "/foo"
+/// 0008  2C 69 66 00                                      tags ",if"
+/// 000C  00 00 00 01                                      [0] i 1
+/// 0010  3F 80 00 00                                      [1] f 1.0
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HexDump<'a>(&'a Packet);
+
+impl<'a> HexDump<'a> {
+    /// Creates a formatter of the packet.
+    pub fn new(packet: &'a Packet) -> HexDump<'a> {
+        HexDump(packet)
+    }
+}
+
+impl Display for HexDump<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let mut bytes = vec![0; self.0.encoded_len()];
+        self.0
+            .encode_into(&mut bytes)
+            .expect("Buffer should have encoded length");
+
+        let mut segments = vec![];
+        push_packet_segments(self.0, 0, 0, &mut segments);
+        for segment in segments {
+            let indent = segment.depth * 2;
+            let chunk = &bytes[segment.range.clone()];
+            if chunk.is_empty() {
+                writeln!(
+                    f,
+                    "{:04X}  {:width$}  {:indent$}{}",
+                    segment.range.start,
+                    "",
+                    "",
+                    segment.label,
+                    width = BYTES_PER_LINE * 3 - 1,
+                )?;
+                continue;
+            }
+
+            for (i, line) in chunk.chunks(BYTES_PER_LINE).enumerate() {
+                write!(f, "{:04X} ", segment.range.start + i * BYTES_PER_LINE)?;
+                for byte in line {
+                    write!(f, " {:02X}", byte)?;
+                }
+                if i == 0 {
+                    let padding = (BYTES_PER_LINE - line.len()) * 3;
+                    write!(f, "{:padding$}  {:indent$}{}", "", "", segment.label)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Labelled range of encoded bytes.
+struct Segment {
+    depth: usize,
+    range: Range<usize>,
+    label: String,
+}
+
+/// Pushes segments of the packet starting at `offset`.
+/// Returns the end offset.
+fn push_packet_segments(
+    packet: &Packet,
+    offset: usize,
+    depth: usize,
+    segments: &mut Vec<Segment>,
+) -> usize {
+    match packet {
+        Packet::Message(m) => push_message_segments(m, offset, depth, segments),
+        Packet::Bundle(b) => push_bundle_segments(b, offset, depth, segments),
+    }
+}
+
+fn push_message_segments(
+    message: &Message,
+    offset: usize,
+    depth: usize,
+    segments: &mut Vec<Segment>,
+) -> usize {
+    let mut offset = offset;
+    let mut push = |length: usize, label: String| {
+        segments.push(Segment {
+            depth,
+            range: offset..(offset + length),
+            label,
+        });
+        offset += length;
+    };
+
+    let address = message.address().as_str();
+    push(
+        Value::aligned_length(address.len() + 1),
+        format!("address {:?}", address),
+    );
+    let type_tags = message.type_tags();
+    push(
+        Value::aligned_length(type_tags.as_str().len() + 1),
+        format!("tags {:?}", type_tags.as_str()),
+    );
+    for (i, argument) in message.arguments().iter().enumerate() {
+        push_argument_segments(argument, format!("[{}]", i), &mut push);
+    }
+
+    offset
+}
+
+fn push_argument_segments(value: &Value, label: String, push: &mut impl FnMut(usize, String)) {
+    match value {
+        Value::Array(values) => {
+            for (i, v) in values.iter().enumerate() {
+                push_argument_segments(v, format!("{}[{}]", label, i), push);
+            }
+        }
+        otherwise => {
+            let tag = TypeTag::of(otherwise).expect("Should not be an array");
+            push(
+                otherwise.encoded_len(),
+                format!("{} {} {}", label, tag.as_char(), otherwise),
+            );
+        }
+    }
+}
+
+fn push_bundle_segments(
+    bundle: &Bundle,
+    offset: usize,
+    depth: usize,
+    segments: &mut Vec<Segment>,
+) -> usize {
+    segments.push(Segment {
+        depth,
+        range: offset..(offset + BUNDLE_HEADER.len()),
+        label: "bundle".into(),
+    });
+    segments.push(Segment {
+        depth,
+        range: (offset + 8)..(offset + 16),
+        label: format!("time tag {:#018X}", bundle.time_tag().0),
+    });
+
+    let mut offset = offset + 16;
+    for (i, element) in bundle.elements().iter().enumerate() {
+        segments.push(Segment {
+            depth,
+            range: offset..(offset + 4),
+            label: format!("[{}] size {}", i, element.encoded_len()),
+        });
+        offset = push_packet_segments(element, offset + 4, depth + 1, segments);
+    }
+    offset
+}
+
+/// Structural differences between two packets.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PacketDiff(Vec<Difference>);
+
+impl PacketDiff {
+    /// Compares two packets.
+    pub fn new(left: &Packet, right: &Packet) -> PacketDiff {
+        let mut differences = vec![];
+        diff_packets(left, right, &mut vec![], &mut differences);
+        PacketDiff(differences)
+    }
+
+    /// Returns found differences.
+    pub fn differences(&self) -> &[Difference] {
+        &self.0
+    }
+
+    /// Whether two packets are structurally the same.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Display for PacketDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        for difference in &self.0 {
+            writeln!(f, "{}", difference)?;
+        }
+        Ok(())
+    }
+}
+
+/// A difference found by `PacketDiff`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// Indices of bundle elements to reach the differing packet, empty for the root.
+    pub path: Vec<usize>,
+
+    /// What differs.
+    pub kind: DifferenceKind,
+}
+
+/// Kinds of `Difference`.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum DifferenceKind {
+    /// One is a message and the other is a bundle.
+    PacketType,
+
+    /// Addresses of messages differ.
+    Address { left: AddressBuf, right: AddressBuf },
+
+    /// Type tags of messages differ.
+    TypeTags { left: TypeTags, right: TypeTags },
+
+    /// Argument values differ. Missing one is `None`.
+    Argument {
+        index: usize,
+        left: Option<Value>,
+        right: Option<Value>,
+    },
+
+    /// Time tags of bundles differ.
+    TimeTag { left: TimeTag, right: TimeTag },
+
+    /// Numbers of bundle elements differ.
+    ElementCount { left: usize, right: usize },
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "packet")?;
+        for index in &self.path {
+            write!(f, "[{}]", index)?;
+        }
+        write!(f, ": ")?;
+
+        match &self.kind {
+            DifferenceKind::PacketType => write!(f, "message and bundle"),
+            DifferenceKind::Address { left, right } => {
+                write!(f, "address {:?} != {:?}", left.as_str(), right.as_str())
+            }
+            DifferenceKind::TypeTags { left, right } => {
+                write!(f, "tags {:?} != {:?}", left.as_str(), right.as_str())
+            }
+            DifferenceKind::Argument { index, left, right } => write!(
+                f,
+                "argument [{}] {} != {}",
+                index,
+                display_optional(left),
+                display_optional(right)
+            ),
+            DifferenceKind::TimeTag { left, right } => {
+                write!(f, "time tag {:#018X} != {:#018X}", left.0, right.0)
+            }
+            DifferenceKind::ElementCount { left, right } => {
+                write!(f, "{} elements != {} elements", left, right)
+            }
+        }
+    }
+}
+
+fn display_optional(value: &Option<Value>) -> String {
+    match value {
+        Some(v) => v.to_string(),
+        None => "(missing)".into(),
+    }
+}
+
+fn diff_packets(
+    left: &Packet,
+    right: &Packet,
+    path: &mut Vec<usize>,
+    differences: &mut Vec<Difference>,
+) {
+    let mut push = |kind| {
+        differences.push(Difference {
+            path: path.clone(),
+            kind,
+        })
+    };
+
+    match (left, right) {
+        (Packet::Message(l), Packet::Message(r)) => {
+            if l.address() != r.address() {
+                push(DifferenceKind::Address {
+                    left: l.address().clone(),
+                    right: r.address().clone(),
+                });
+            }
+            let (left_tags, right_tags) = (l.type_tags(), r.type_tags());
+            if left_tags != right_tags {
+                push(DifferenceKind::TypeTags {
+                    left: left_tags,
+                    right: right_tags,
+                });
+            }
+
+            let count = l.arguments().len().max(r.arguments().len());
+            for index in 0..count {
+                let left = l.arguments().get(index);
+                let right = r.arguments().get(index);
+                // NaN is regarded as the same as itself.
+                if left.cloned().map(CanonicalValue) != right.cloned().map(CanonicalValue) {
+                    push(DifferenceKind::Argument {
+                        index,
+                        left: left.cloned(),
+                        right: right.cloned(),
+                    });
+                }
+            }
+        }
+        (Packet::Bundle(l), Packet::Bundle(r)) => {
+            if l.time_tag() != r.time_tag() {
+                push(DifferenceKind::TimeTag {
+                    left: l.time_tag(),
+                    right: r.time_tag(),
+                });
+            }
+            if l.elements().len() != r.elements().len() {
+                push(DifferenceKind::ElementCount {
+                    left: l.elements().len(),
+                    right: r.elements().len(),
+                });
+            }
+
+            for (i, (le, re)) in l.elements().iter().zip(r.elements()).enumerate() {
+                path.push(i);
+                diff_packets(le, re, path, differences);
+                path.pop();
+            }
+        }
+        _ => push(DifferenceKind::PacketType),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Difference, DifferenceKind, HexDump, PacketDiff};
+    use crate::{
+        data::{TimeTag, Value},
+        packet::{Bundle, MessageBuilder, Packet},
+    };
+
+    use alloc::{string::ToString, vec, vec::Vec};
+
+    fn message(address: &str, arguments: Vec<Value>) -> Packet {
+        Packet::Message(
+            MessageBuilder::new(address)
+                .expect("Should valid")
+                .set_arguments(arguments)
+                .build(),
+        )
+    }
+
+    /// Ensures each part of a message is labelled.
+    #[test]
+    fn test_hexdump_message() {
+        let packet = message(
+            "/foo",
+            vec![
+                Value::Int32(1),
+                Value::Array(vec![Value::Boolean(true), Value::Float32(1.0)]),
+            ],
+        );
+        let expected = concat!(
+            "0000  2F 66 6F 6F 00 00 00 00                          address \"/foo\"\n",
+            "0008  2C 69 5B 54 66 5D 00 00                          tags \",i[Tf]\"\n",
+            "0010  00 00 00 01                                      [0] i 1\n",
+            "0014                                                   [1][0] T true\n",
+            "0014  3F 80 00 00                                      [1][1] f 1.0\n",
+        );
+        assert_eq!(HexDump::new(&packet).to_string(), expected);
+    }
+
+    /// Ensures bundles are dumped with nested elements indented, and long data wrapped.
+    #[test]
+    fn test_hexdump_bundle() {
+        let packet = Packet::Bundle(Bundle::new(
+            TimeTag(1),
+            vec![message(
+                "/a",
+                vec![Value::String("0123456789abcdef".into())],
+            )],
+        ));
+        let expected = concat!(
+            "0000  23 62 75 6E 64 6C 65 00                          bundle\n",
+            "0008  00 00 00 00 00 00 00 01                          time tag 0x0000000000000001\n",
+            "0010  00 00 00 1C                                      [0] size 28\n",
+            "0014  2F 61 00 00                                        address \"/a\"\n",
+            "0018  2C 73 00 00                                        tags \",s\"\n",
+            "001C  30 31 32 33 34 35 36 37 38 39 61 62 63 64 65 66    [0] s \"0123456789abcdef\"\n",
+            "002C  00 00 00 00\n",
+        );
+        assert_eq!(HexDump::new(&packet).to_string(), expected);
+    }
+
+    /// Ensures differences are reported with paths.
+    #[test]
+    fn test_packet_diff() {
+        let left = Packet::Bundle(Bundle::new(
+            TimeTag(1),
+            vec![
+                message("/a", vec![Value::Int32(1), Value::Float32(f32::NAN)]),
+                message("/b", vec![]),
+            ],
+        ));
+        let right = Packet::Bundle(Bundle::new(
+            TimeTag(1),
+            vec![
+                message(
+                    "/a",
+                    vec![Value::Float32(1.0), Value::Float32(f32::NAN), Value::Nil],
+                ),
+                Packet::Bundle(Bundle::new(TimeTag(2), vec![])),
+            ],
+        ));
+
+        let diff = PacketDiff::new(&left, &right);
+        assert_eq!(
+            diff.differences()[1],
+            Difference {
+                path: vec![0],
+                kind: DifferenceKind::Argument {
+                    index: 0,
+                    left: Some(Value::Int32(1)),
+                    right: Some(Value::Float32(1.0)),
+                },
+            }
+        );
+        assert_eq!(
+            diff.to_string(),
+            concat!(
+                "packet[0]: tags \",if\" != \",ffN\"\n",
+                "packet[0]: argument [0] 1 != 1.0\n",
+                "packet[0]: argument [2] (missing) != Nil\n",
+                "packet[1]: message and bundle\n",
+            )
+        );
+
+        assert!(PacketDiff::new(&left, &left.clone()).is_empty());
+    }
+}
@@ -1,20 +1,48 @@
 //! OSC (Open Sound Control) protocol implementation.
+//!
+//! This crate is `no_std` with `alloc` when `std` feature is disabled.
+//! Capturing and address pattern matching require `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 pub mod address;
+#[cfg(feature = "std")]
+pub mod capture;
 pub mod data;
+mod encode;
 pub mod error;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub mod generator;
+pub mod inspect;
 pub mod packet;
+pub mod signature;
+#[cfg(feature = "transform")]
+pub mod transform;
 
 /// Prelude module, prefixed with `Osc` to avoid identifier conflict.
 pub mod prelude {
-    pub use crate::address::Address as OscAddress;
-    pub use crate::data::Value as OscValue;
+    pub use crate::address::{
+        Address as OscAddress, AddressBuf as OscAddressBuf, AddressStr as OscAddressStr,
+    };
+    pub use crate::data::{CanonicalValue as OscCanonicalValue, Value as OscValue};
     pub use crate::error::{Error as OscError, Result as OscResult};
+    pub use crate::inspect::{HexDump as OscHexDump, PacketDiff as OscPacketDiff};
     pub use crate::packet::{
         Bundle as OscBundle, Message as OscMessage, MessageBuilder as OscMessageBuilder,
         Packet as OscPacket,
     };
+    pub use crate::signature::{
+        SignatureRegistry as OscSignatureRegistry, TypeTag as OscTypeTag, TypeTags as OscTypeTags,
+    };
 
     #[cfg(feature = "address-pattern")]
     pub use crate::address::{AddressPattern as OscAddressPattern, Expression as OscExpression};
+
+    #[cfg(feature = "transform")]
+    pub use crate::transform::{
+        Pipeline as OscPipeline, Transform as OscTransform, TransformError as OscTransformError,
+        TransformResult as OscTransformResult,
+    };
 }
@@ -1,14 +1,21 @@
 //! OSC bundle manipulations.
 
+use alloc::{boxed::Box, vec, vec::Vec};
+
 use crate::{
     data::TimeTag,
+    encode::SliceWriter,
     error::{Error, Result},
-    packet::Packet,
+    packet::{Message, Packet},
 };
 
 /// OSC-string of OSC-bundle header.
 pub const BUNDLE_HEADER: &[u8] = b"#bundle\0";
 
+/// Maximum nesting depth of bundles in deserialized packet.
+/// Deeper bundles are rejected so that decoding untrusted data cannot exhaust the stack.
+pub const MAX_BUNDLE_DEPTH: usize = 32;
+
 /// Represents an OSC Bundle data.
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Bundle {
@@ -17,6 +24,14 @@ pub struct Bundle {
 }
 
 impl Bundle {
+    /// Creates a bundle from time tag and elements.
+    pub fn new(time_tag: TimeTag, elements: Vec<Packet>) -> Bundle {
+        Bundle {
+            time_tag,
+            elements: elements.into_boxed_slice(),
+        }
+    }
+
     /// Returns timetag.
     pub fn time_tag(&self) -> TimeTag {
         self.time_tag
@@ -32,23 +47,136 @@ impl Bundle {
         (self.time_tag, self.elements.into())
     }
 
+    /// Checks that time tags of nested bundles are not earlier than enclosing ones.
+    /// Nested bundles with "immediately" time tag are regarded as having the enclosing one.
+    pub fn validate_time_tags(&self) -> Result<()> {
+        self.validate_with(self.time_tag)
+    }
+
+    /// Validates nested bundles against `effective`, the effective time tag of this bundle.
+    /// Bundles are visited with explicit stack, so that deep nesting does not exhaust the stack.
+    fn validate_with(&self, effective: TimeTag) -> Result<()> {
+        let mut pending = vec![(self, effective)];
+        while let Some((bundle, effective)) = pending.pop() {
+            for element in &bundle.elements[..] {
+                if let Packet::Bundle(nested) = element {
+                    check_nested_time_tag(effective, nested.time_tag)?;
+                    pending.push((nested, nested.effective_time_tag(effective)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the effective time tag of this bundle enclosed by one with `enclosing`.
+    fn effective_time_tag(&self, enclosing: TimeTag) -> TimeTag {
+        if self.time_tag.is_immediate() {
+            enclosing
+        } else {
+            self.time_tag
+        }
+    }
+
+    /// Iterates all messages in this bundle and nested bundles,
+    /// with effective time tags in delivery order.
+    ///
+    /// Messages are sorted by effective time tag, and ones with the same time tag keep the order in the bundle.
+    /// Effective time tag of a nested bundle with "immediately" is the enclosing one.
+    pub fn flatten(&self) -> impl Iterator<Item = (TimeTag, &Message)> + '_ {
+        let mut messages = vec![];
+        self.collect_messages(self.time_tag, &mut messages);
+        messages.sort_by_key(|(time_tag, _)| *time_tag);
+        messages.into_iter()
+    }
+
+    /// Collects messages in depth-first order with explicit stack,
+    /// so that deep nesting does not exhaust the stack.
+    fn collect_messages<'a>(
+        &'a self,
+        time_tag: TimeTag,
+        messages: &mut Vec<(TimeTag, &'a Message)>,
+    ) {
+        let mut stack = vec![(time_tag, self.elements.iter())];
+        while let Some((time_tag, elements)) = stack.last_mut() {
+            let time_tag = *time_tag;
+            match elements.next() {
+                Some(Packet::Message(m)) => messages.push((time_tag, m)),
+                Some(Packet::Bundle(b)) => {
+                    stack.push((b.effective_time_tag(time_tag), b.elements.iter()))
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+
     /// Serializes this packet.
     /// Returned bytes **does not** contain the whole size.
     pub fn serialize(self) -> Box<[u8]> {
-        let mut bytes = Vec::with_capacity(16);
-        bytes.extend_from_slice(BUNDLE_HEADER);
-        bytes.extend_from_slice(&self.time_tag.0.to_be_bytes());
-        for element in self.elements.to_vec() {
-            bytes.extend_from_slice(&element.serialize());
-        }
-
+        let mut bytes = vec![0; self.encoded_len()];
+        self.encode_into(&mut bytes)
+            .expect("Buffer should have encoded length");
         bytes.into_boxed_slice()
     }
+
+    /// Returns the length of bytes written by `encode_into`.
+    pub fn encoded_len(&self) -> usize {
+        16 + self
+            .elements
+            .iter()
+            .map(|e| 4 + e.encoded_len())
+            .sum::<usize>()
+    }
+
+    /// Encodes this packet into `buffer` without allocation.
+    /// Written bytes are the same as `serialize`, and **does not** contain the whole size.
+    /// Returns the written length.
+    pub fn encode_into(&self, buffer: &mut [u8]) -> Result<usize> {
+        let mut writer = SliceWriter::new(buffer);
+        self.encode_with(&mut writer)?;
+        Ok(writer.position())
+    }
+
+    /// Appends encoded bytes to `buffer`, reusing its capacity.
+    /// Written bytes are the same as `serialize`, and **does not** contain the whole size.
+    pub fn encode_to_vec(&self, buffer: &mut Vec<u8>) {
+        let start = buffer.len();
+        buffer.resize(start + self.encoded_len(), 0);
+        self.encode_into(&mut buffer[start..])
+            .expect("Buffer should have encoded length");
+    }
+
+    /// Writes this packet, with size of each element.
+    pub(crate) fn encode_with(&self, writer: &mut SliceWriter<'_>) -> Result<()> {
+        writer.write(BUNDLE_HEADER)?;
+        writer.write(&self.time_tag.0.to_be_bytes())?;
+        for element in &self.elements[..] {
+            let size_position = writer.position();
+            writer.write(&[0; 4])?;
+            element.encode_with(writer)?;
+
+            let size = (writer.position() - size_position - 4) as i32;
+            writer.write_at(size_position, &size.to_be_bytes());
+        }
+        Ok(())
+    }
 }
 
 impl Bundle {
     /// Deserializes an OSC Bundle.
+    /// Time tags of nested bundles are validated as in `validate_time_tags`,
+    /// and bundles nested deeper than `MAX_BUNDLE_DEPTH` are rejected.
     pub fn deserialize(bytes: &[u8]) -> Result<Bundle> {
+        let bundle = Bundle::deserialize_unvalidated(bytes, 0)?;
+        bundle.validate_time_tags()?;
+        Ok(bundle)
+    }
+
+    /// Deserializes an OSC Bundle without validating time tags.
+    /// Nested bundles are validated at once by the outermost one.
+    /// `depth` is the nesting depth of this bundle.
+    fn deserialize_unvalidated(bytes: &[u8], depth: usize) -> Result<Bundle> {
         if bytes.len() < 16 || &bytes[..8] != BUNDLE_HEADER {
             return Err(Error::InvalidBundle);
         }
@@ -66,7 +194,13 @@ impl Bundle {
 
             // TODO: parse rest_bytes
             let element_bytes = &rest_bytes[4..(length + 4)];
-            let element = Packet::deserialize(element_bytes)?;
+            let element = match element_bytes.first() {
+                Some(b'#') if depth == MAX_BUNDLE_DEPTH => return Err(Error::TooDeep),
+                Some(b'#') => {
+                    Packet::Bundle(Bundle::deserialize_unvalidated(element_bytes, depth + 1)?)
+                }
+                _ => Packet::deserialize(element_bytes)?,
+            };
             elements.push(element);
 
             rest_bytes = &rest_bytes[(length + 4)..];
@@ -78,3 +212,183 @@ impl Bundle {
         })
     }
 }
+
+fn check_nested_time_tag(enclosing: TimeTag, nested: TimeTag) -> Result<()> {
+    if nested.is_immediate() || nested >= enclosing {
+        Ok(())
+    } else {
+        Err(Error::InvalidTimeTag { enclosing, nested })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MAX_BUNDLE_DEPTH;
+    use crate::{
+        data::{TimeTag, Value},
+        error::Error,
+        packet::{Bundle, Message, MessageBuilder, Packet},
+    };
+
+    use alloc::{vec, vec::Vec};
+
+    fn message(address: &str) -> Message {
+        MessageBuilder::new(address)
+            .expect("Should valid")
+            .push_argument(Value::Int32(1))
+            .build()
+    }
+
+    /// Ensures nested bundles earlier than enclosing ones are rejected.
+    #[test]
+    fn test_deserialize_nested_time_tag() {
+        let valid = Bundle::new(
+            TimeTag(100),
+            vec![
+                Packet::Bundle(Bundle::new(TimeTag(100), vec![])),
+                Packet::Bundle(Bundle::new(
+                    TimeTag::IMMEDIATELY,
+                    vec![Packet::Bundle(Bundle::new(TimeTag(200), vec![]))],
+                )),
+            ],
+        );
+        assert_eq!(valid.validate_time_tags(), Ok(()));
+        assert_eq!(Bundle::deserialize(&valid.clone().serialize()), Ok(valid));
+
+        let invalid = Bundle::new(
+            TimeTag(100),
+            vec![Packet::Bundle(Bundle::new(
+                TimeTag(200),
+                vec![Packet::Bundle(Bundle::new(TimeTag(150), vec![]))],
+            ))],
+        );
+        let expected = Error::InvalidTimeTag {
+            enclosing: TimeTag(200),
+            nested: TimeTag(150),
+        };
+        assert_eq!(invalid.validate_time_tags(), Err(expected.clone()));
+        assert_eq!(Bundle::deserialize(&invalid.serialize()), Err(expected));
+
+        // Immediate bundles do not reset the time tag their children are compared with.
+        let through_immediate = Bundle::new(
+            TimeTag(100),
+            vec![Packet::Bundle(Bundle::new(
+                TimeTag::IMMEDIATELY,
+                vec![Packet::Bundle(Bundle::new(TimeTag(50), vec![]))],
+            ))],
+        );
+        let expected = Error::InvalidTimeTag {
+            enclosing: TimeTag(100),
+            nested: TimeTag(50),
+        };
+        assert_eq!(
+            through_immediate.validate_time_tags(),
+            Err(expected.clone())
+        );
+        assert_eq!(
+            Bundle::deserialize(&through_immediate.serialize()),
+            Err(expected)
+        );
+    }
+
+    /// Ensures flattened messages have effective time tags in delivery order.
+    #[test]
+    fn test_flatten() {
+        let bundle = Bundle::new(
+            TimeTag(100),
+            vec![
+                Packet::Bundle(Bundle::new(
+                    TimeTag(300),
+                    vec![Packet::Message(message("/c"))],
+                )),
+                Packet::Message(message("/a")),
+                Packet::Bundle(Bundle::new(
+                    TimeTag(200),
+                    vec![
+                        Packet::Message(message("/b1")),
+                        Packet::Bundle(Bundle::new(
+                            TimeTag::IMMEDIATELY,
+                            vec![Packet::Message(message("/b2"))],
+                        )),
+                    ],
+                )),
+                Packet::Message(message("/a2")),
+            ],
+        );
+
+        let flattened: Vec<_> = bundle
+            .flatten()
+            .map(|(t, m)| (t, m.address().as_str()))
+            .collect();
+        assert_eq!(
+            flattened,
+            vec![
+                (TimeTag(100), "/a"),
+                (TimeTag(100), "/a2"),
+                (TimeTag(200), "/b1"),
+                (TimeTag(200), "/b2"),
+                (TimeTag(300), "/c"),
+            ]
+        );
+    }
+
+    /// Ensures that `Bundle::serialize()` prefixes each element with its size.
+    #[test]
+    fn test_serializer_element_size() {
+        let first = MessageBuilder::new("/a")
+            .expect("Invalid address")
+            .push_argument(Value::Int32(1))
+            .build();
+        let second = MessageBuilder::new("/bc").expect("Invalid address").build();
+        let bundle = Bundle {
+            time_tag: TimeTag(0x0102030405060708),
+            elements: vec![Packet::Message(first), Packet::Message(second)].into_boxed_slice(),
+        };
+
+        let expected = vec![
+            b'#', b'b', b'u', b'n', // Header
+            b'd', b'l', b'e', 0x00, // Header
+            0x01, 0x02, 0x03, 0x04, // Time tag
+            0x05, 0x06, 0x07, 0x08, // Time tag
+            0x00, 0x00, 0x00, 0x0C, // Size of first element
+            b'/', b'a', 0x00, 0x00, // Address
+            b',', b'i', 0x00, 0x00, // Tag
+            0x00, 0x00, 0x00, 0x01, // Argument
+            0x00, 0x00, 0x00, 0x08, // Size of second element
+            b'/', b'b', b'c', 0x00, // Address
+            b',', 0x00, 0x00, 0x00, // Tag
+        ];
+        assert_eq!(&bundle.clone().serialize()[..], &expected[..]);
+        assert_eq!(
+            Packet::deserialize(&expected).expect("Deserialize failed"),
+            Packet::Bundle(bundle)
+        );
+    }
+
+    /// Builds bytes of bundles nested `levels` deep, each containing only the next one.
+    fn nested_bundle_bytes(levels: usize) -> Vec<u8> {
+        let mut bytes = vec![];
+        for depth in 0..levels {
+            bytes.extend_from_slice(b"#bundle\0");
+            bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]);
+            if depth + 1 < levels {
+                let nested_length = 16 + 20 * (levels - depth - 2);
+                bytes.extend_from_slice(&(nested_length as i32).to_be_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Ensures that deeply nested bundles are rejected without overflowing.
+    #[test]
+    fn test_deserialize_too_deep() {
+        let deepest = nested_bundle_bytes(MAX_BUNDLE_DEPTH + 1);
+        let bundle = Bundle::deserialize(&deepest).expect("Should deserialize");
+        assert_eq!(bundle.flatten().count(), 0);
+
+        let too_deep = nested_bundle_bytes(MAX_BUNDLE_DEPTH + 2);
+        assert_eq!(Bundle::deserialize(&too_deep), Err(Error::TooDeep));
+        let too_deep = nested_bundle_bytes(10000);
+        assert_eq!(Packet::deserialize(&too_deep), Err(Error::TooDeep));
+    }
+}
@@ -1,11 +1,14 @@
 //! Contains manipulation and types about OSC Packet.
 
-use std::str::from_utf8;
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use core::str::from_utf8;
 
 use crate::{
-    address::Address,
+    address::{Address, AddressBuf},
     data::{TimeTag, Value},
+    encode::SliceWriter,
     error::{Error, Result},
+    signature::{validate_type_tags, TypeTags},
 };
 
 /// Represents an immutable OSC packet.
@@ -26,38 +29,75 @@ impl Message {
         &self.arguments
     }
 
+    /// Returns type tags of arguments.
+    pub fn type_tags(&self) -> TypeTags {
+        TypeTags::from_values(&self.arguments)
+    }
+
     /// Consumes itself and splits into owned path and arguments.
     pub fn split_into(self) -> (String, Vec<Value>) {
         (self.address.into(), self.arguments.into())
     }
 
+    /// Consumes itself and splits into owned address and arguments.
+    pub fn into_parts(self) -> (AddressBuf, Vec<Value>) {
+        (self.address, self.arguments.into())
+    }
+
     /// Serialize this packet.
     /// Returned bytes **does not** contain the whole size.
     pub fn serialize(self) -> Box<[u8]> {
-        let mut serialized_bytes = Vec::with_capacity(32);
+        let mut serialized_bytes = vec![0; self.encoded_len()];
+        self.encode_into(&mut serialized_bytes)
+            .expect("Buffer should have encoded length");
+        serialized_bytes.into_boxed_slice()
+    }
 
-        // Address
-        let mut terminated_address: Vec<u8> = self.address.into_string().into_bytes();
-        terminated_address.push(0);
-        Value::align_bytes(&mut terminated_address);
-        serialized_bytes.append(&mut terminated_address);
+    /// Returns the length of bytes written by `encode_into`.
+    pub fn encoded_len(&self) -> usize {
+        let tags_length = 1 + self
+            .arguments
+            .iter()
+            .map(Value::type_tag_len)
+            .sum::<usize>();
+        Value::aligned_length(self.address.as_str().len() + 1)
+            + Value::aligned_length(tags_length + 1)
+            + self.arguments.iter().map(Value::encoded_len).sum::<usize>()
+    }
 
-        // Tags
-        let mut type_tags: String = ",".into();
+    /// Encodes this packet into `buffer` without allocation.
+    /// Written bytes are the same as `serialize`, and **does not** contain the whole size.
+    /// Returns the written length.
+    pub fn encode_into(&self, buffer: &mut [u8]) -> Result<usize> {
+        let mut writer = SliceWriter::new(buffer);
+        self.encode_with(&mut writer)?;
+        Ok(writer.position())
+    }
+
+    /// Appends encoded bytes to `buffer`, reusing its capacity.
+    /// Written bytes are the same as `serialize`, and **does not** contain the whole size.
+    pub fn encode_to_vec(&self, buffer: &mut Vec<u8>) {
+        let start = buffer.len();
+        buffer.resize(start + self.encoded_len(), 0);
+        self.encode_into(&mut buffer[start..])
+            .expect("Buffer should have encoded length");
+    }
+
+    /// Writes this packet.
+    pub(crate) fn encode_with(&self, writer: &mut SliceWriter<'_>) -> Result<()> {
+        writer.write_string(self.address.as_str().as_bytes())?;
+
+        writer.write(b",")?;
         for arg in &self.arguments[..] {
-            arg.push_type_tag_to(&mut type_tags);
-        }
-        let mut type_tags: Vec<u8> = type_tags.into_bytes();
-        type_tags.push(0);
-        Value::align_bytes(&mut type_tags);
-        serialized_bytes.append(&mut type_tags);
-
-        // Data
-        for arg in Vec::from(self.arguments) {
-            arg.write_aligned_into(&mut serialized_bytes);
+            arg.encode_type_tag_with(writer)?;
         }
+        writer.write(&[0])?;
+        writer.pad()?;
 
-        serialized_bytes.into_boxed_slice()
+        for arg in &self.arguments[..] {
+            arg.encode_with(writer)?;
+        }
+        Ok(())
     }
 }
 
@@ -65,10 +105,11 @@ impl Message {
     /// Deserializes bytes into packet.
     pub fn deserialize(bytes: &[u8]) -> Result<Message> {
         let (address, tag, argument_bytes) = Message::split_bytes(bytes)?;
+        let count = validate_type_tags(tag.as_bytes())?;
 
-        let mut arguments = vec![];
+        let mut arguments = Vec::with_capacity(count);
         let mut rest_tag = &tag.as_bytes()[1..];
-        let mut rest_argument = &argument_bytes[..];
+        let mut rest_argument = argument_bytes;
         while !rest_tag.is_empty() {
             let (arg, next_tag, next_argument) = Message::parse_argument(rest_tag, rest_argument)?;
             rest_tag = next_tag;
@@ -83,15 +124,15 @@ impl Message {
     }
 
     /// Splits raw bytes array into address, types tag, and argument data.
-    /// Returned address and tag are guaranteed that they have correct leaders and consist of only ASCII-bytes.
-    fn split_bytes(bytes: &[u8]) -> Result<(Address, String, &[u8])> {
+    /// Returned address is guaranteed to be valid, but the tag is not validated yet.
+    fn split_bytes(bytes: &[u8]) -> Result<(Address, &str, &[u8])> {
         // Check alignment
-        if bytes.len() % 4 != 0 {
+        if !bytes.len().is_multiple_of(4) {
             return Err(Error::UnalignedData);
         }
 
         // Slice address
-        let rest_bytes = &bytes[..];
+        let rest_bytes = bytes;
         let address_first_nul = match rest_bytes.iter().position(|&b| b == 0x00) {
             None => return Err(Error::NotTerminated),
             Some(0) => return Err(Error::InvalidAddress),
@@ -109,12 +150,8 @@ impl Message {
             Some(0) => return Err(Error::InvalidTag),
             Some(i) => i,
         };
-        let tag: Vec<u8> = (&rest_bytes[..tag_first_nul]).into();
-        let tag = String::from_utf8(tag).map_err(|_| Error::InvalidTag)?;
+        let tag = from_utf8(&rest_bytes[..tag_first_nul]).map_err(|_| Error::InvalidTag)?;
         let tag_aligned = Value::aligned_length(tag_first_nul + 1);
-        if !tag.starts_with(',') || !tag.is_ascii() {
-            return Err(Error::InvalidTag);
-        }
 
         // Cut out arguments
         let arguments_left = &bytes[(address_aligned + tag_aligned)..];
@@ -280,8 +317,8 @@ impl Message {
                 }
 
                 let length =
-                    i32::from_be_bytes(rest_argument[..4].try_into().expect("Wrong length"))
-                        as usize;
+                    i32::from_be_bytes(rest_argument[..4].try_into().expect("Wrong length"));
+                let length = usize::try_from(length).map_err(|_| Error::NotEnoughData)?;
                 let aligned_length = Value::aligned_length(length);
 
                 if rest_argument.len() < aligned_length + 4 {
@@ -292,7 +329,7 @@ impl Message {
                 Ok((
                     Value::Blob(blob),
                     &rest_tag[1..],
-                    &rest_argument[aligned_length..],
+                    &rest_argument[(aligned_length + 4)..],
                 ))
             }
             b'[' => {
@@ -301,11 +338,16 @@ impl Message {
                 let mut inner_rest_argument = rest_argument;
 
                 loop {
-                    if rest_argument.is_empty() {
-                        break Err(Error::IllegalStructure);
-                    }
-                    if rest_argument[0] == b']' {
-                        break Ok((Value::Array(values), &rest_tag[1..], rest_argument));
+                    match inner_rest_tag.first() {
+                        None => break Err(Error::IllegalStructure),
+                        Some(b']') => {
+                            break Ok((
+                                Value::Array(values),
+                                &inner_rest_tag[1..],
+                                inner_rest_argument,
+                            ))
+                        }
+                        Some(_) => (),
                     }
 
                     let (arg, next_tag, next_argument) =
@@ -337,6 +379,14 @@ impl MessageBuilder {
         })
     }
 
+    /// Creates new builder with validated address.
+    pub fn from_address(address: AddressBuf) -> MessageBuilder {
+        MessageBuilder {
+            address,
+            arguments: vec![],
+        }
+    }
+
     /// Builds immutable `Packet`.
     pub fn build(self) -> Message {
         Message {
@@ -361,11 +411,16 @@ impl MessageBuilder {
 #[cfg(test)]
 mod test {
     use crate::{
-        data::Value,
+        data::{TimeTag, Value},
         error::Error,
-        packet::message::{Message, MessageBuilder},
+        packet::{
+            message::{Message, MessageBuilder},
+            Bundle, Packet,
+        },
     };
 
+    use alloc::{string::String, vec, vec::Vec};
+
     /// Ensures that `Packet::deserialize()` processes valid bytes.
     /// The packet has only address.
     #[test]
@@ -450,4 +505,174 @@ mod test {
             ]
         );
     }
+
+    /// Minimal xorshift generator for deterministic property-style tests.
+    struct Generator(u64);
+
+    impl Generator {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> usize {
+            (self.next() % n) as usize
+        }
+
+        fn string(&mut self) -> String {
+            let length = self.below(9);
+            (0..length)
+                .map(|_| (b' ' + self.below(95) as u8) as char)
+                .collect()
+        }
+
+        fn value(&mut self, depth: usize) -> Value {
+            let variants = if depth == 0 { 14 } else { 15 };
+            match self.below(variants) {
+                0 => Value::Nil,
+                1 => Value::Infinitum,
+                2 => Value::Boolean(self.next() & 1 == 0),
+                3 => Value::Character((b' ' + self.below(95) as u8) as char),
+                4 => Value::Int32(self.next() as i32),
+                5 => Value::Int64(self.next() as i64),
+                6 => Value::Float32(self.next() as i32 as f32 / 1024.0),
+                7 => Value::Float64(self.next() as i64 as f64 / 65536.0),
+                8 => Value::Color((self.next() as u32).to_be_bytes()),
+                9 => Value::MidiMessage((self.next() as u32).to_be_bytes()),
+                10 => Value::TimeTag(TimeTag(self.next())),
+                11 => Value::String(self.string()),
+                12 => Value::Alternative(self.string()),
+                13 => {
+                    let length = self.below(10);
+                    Value::Blob((0..length).map(|_| self.next() as u8).collect())
+                }
+                _ => {
+                    let length = self.below(4);
+                    Value::Array((0..length).map(|_| self.value(depth - 1)).collect())
+                }
+            }
+        }
+    }
+
+    /// Ensures nested arrays and blobs are decoded from hand-written bytes.
+    #[test]
+    fn test_deserializer_array_blob() {
+        let bytes = vec![
+            b'/', b'p', b'a', b't', // Address
+            b'h', 0x00, 0x00, 0x00, // Address
+            b',', b'[', b'i', b'[', // Tag
+            b'b', b']', b']', b'i', // Tag
+            0x00, 0x00, 0x00, 0x00, // Tag
+            0x00, 0x00, 0x00, 0x01, // Int32
+            0x00, 0x00, 0x00, 0x05, // Blob length
+            0x01, 0x02, 0x03, 0x04, // Blob
+            0x05, 0x00, 0x00, 0x00, // Blob
+            0x00, 0x00, 0x00, 0x02, // Int32
+        ];
+
+        let packet = Message::deserialize(&bytes).expect("Deserialize failed");
+        assert_eq!(
+            packet.arguments(),
+            &[
+                Value::Array(vec![
+                    Value::Int32(1),
+                    Value::Array(vec![Value::Blob(vec![1, 2, 3, 4, 5])]),
+                ]),
+                Value::Int32(2),
+            ]
+        );
+    }
+
+    /// Ensures every value variant survives serialization and deserialization,
+    /// including arrays nested up to three levels.
+    #[test]
+    fn test_roundtrip_generated() {
+        let mut generator = Generator(0x9E37_79B9_7F4A_7C15);
+        for depth in 0..=3 {
+            for _ in 0..256 {
+                let arguments: Vec<_> = (0..generator.below(6))
+                    .map(|_| generator.value(depth))
+                    .collect();
+                let message = MessageBuilder::new("/roundtrip/test")
+                    .expect("Should valid")
+                    .set_arguments(arguments)
+                    .build();
+
+                let bytes = message.clone().serialize();
+                let decoded = Message::deserialize(&bytes).expect("Should deserialize");
+                assert_eq!(decoded, message);
+            }
+        }
+    }
+
+    /// Ensures allocation-free encoding produces the same bytes as serialization,
+    /// and encoding into `Vec` appends them.
+    #[test]
+    fn test_encode_into_generated() {
+        let mut generator = Generator(0xD1B5_4A32_D192_ED03);
+        let mut buffer = [0u8; 4096];
+        let mut reused = vec![];
+        for _ in 0..256 {
+            let arguments: Vec<_> = (0..generator.below(6))
+                .map(|_| generator.value(3))
+                .collect();
+            let message = MessageBuilder::new("/encode/test")
+                .expect("Should valid")
+                .set_arguments(arguments)
+                .build();
+            let packet = Packet::Bundle(Bundle::new(
+                TimeTag(generator.next()),
+                vec![Packet::Message(message.clone())],
+            ));
+
+            for packet in [Packet::Message(message), packet] {
+                let serialized = packet.clone().serialize();
+                let length = packet.encode_into(&mut buffer).expect("Should encode");
+                assert_eq!(length, packet.encoded_len());
+                assert_eq!(&buffer[..length], &serialized[..]);
+                assert_eq!(
+                    packet.encode_into(&mut buffer[..(length - 1)]),
+                    Err(Error::BufferTooSmall)
+                );
+
+                reused.clear();
+                reused.push(0xFF);
+                packet.encode_to_vec(&mut reused);
+                assert_eq!(&reused[1..], &serialized[..]);
+            }
+        }
+    }
+
+    /// Ensures truncated blobs and unterminated arrays are rejected.
+    #[test]
+    fn test_deserializer_error_array_blob() {
+        let bytes = vec![
+            b'/', b'p', b'a', b't', // Address
+            b'h', 0x00, 0x00, 0x00, // Address
+            b',', b'b', 0x00, 0x00, // Tag
+            0x00, 0x00, 0x00, 0x08, // Blob length
+            0x01, 0x02, 0x03, 0x04, // Blob
+        ];
+        assert_eq!(Message::deserialize(&bytes), Err(Error::NotEnoughData));
+
+        let bytes = vec![
+            b'/', b'p', b'a', b't', // Address
+            b'h', 0x00, 0x00, 0x00, // Address
+            b',', b'[', b'i', 0x00, // Tag
+            0x00, 0x00, 0x00, 0x01, // Int32
+        ];
+        assert_eq!(Message::deserialize(&bytes), Err(Error::IllegalStructure));
+    }
+
+    /// Ensures that `Message::deserialize()` rejects deeply nested arrays without overflowing.
+    #[test]
+    fn test_deserializer_error_too_deep() {
+        let mut bytes = b"/path\0\0\0,".to_vec();
+        bytes.extend_from_slice(&[b'['; 10000]);
+        bytes.extend_from_slice(&[b']'; 10000]);
+        bytes.resize(Value::aligned_length(bytes.len() + 1), 0x00);
+        assert_eq!(Message::deserialize(&bytes), Err(Error::TooDeep));
+    }
 }
@@ -6,7 +6,12 @@ pub use crate::packet::{
     message::{Message, MessageBuilder},
 };
 
-use crate::error::{Error, Result};
+use crate::{
+    encode::SliceWriter,
+    error::{Error, Result},
+};
+
+use alloc::{boxed::Box, vec::Vec};
 
 /// Represents a whole OSC Packet.
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
@@ -28,9 +33,44 @@ impl Packet {
         }
     }
 
+    /// Returns the length of bytes written by `encode_into`.
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            Packet::Message(m) => m.encoded_len(),
+            Packet::Bundle(b) => b.encoded_len(),
+        }
+    }
+
+    /// Encodes this packet into `buffer` without allocation.
+    /// Written bytes are the same as `serialize`, and **does not** contain the whole size.
+    /// Returns the written length.
+    pub fn encode_into(&self, buffer: &mut [u8]) -> Result<usize> {
+        match self {
+            Packet::Message(m) => m.encode_into(buffer),
+            Packet::Bundle(b) => b.encode_into(buffer),
+        }
+    }
+
+    /// Appends encoded bytes to `buffer`, reusing its capacity.
+    /// Written bytes are the same as `serialize`, and **does not** contain the whole size.
+    pub fn encode_to_vec(&self, buffer: &mut Vec<u8>) {
+        match self {
+            Packet::Message(m) => m.encode_to_vec(buffer),
+            Packet::Bundle(b) => b.encode_to_vec(buffer),
+        }
+    }
+
+    /// Writes this packet.
+    pub(crate) fn encode_with(&self, writer: &mut SliceWriter<'_>) -> Result<()> {
+        match self {
+            Packet::Message(m) => m.encode_with(writer),
+            Packet::Bundle(b) => b.encode_with(writer),
+        }
+    }
+
     /// Deserializes the bytes into OSC Packet.
     pub fn deserialize(bytes: &[u8]) -> Result<Packet> {
-        if bytes.len() == 0 {
+        if bytes.is_empty() {
             return Err(Error::NotEnoughData);
         }
 
@@ -0,0 +1,528 @@
+//! Type tag strings and per-address signature validation.
+
+use crate::{
+    address::{AddressBuf, AddressStr},
+    data::Value,
+    error::{Error, Result},
+    packet::{Message, MessageBuilder},
+};
+
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+/// Maximum nesting depth of arrays in type tag string.
+/// Deeper arrays are rejected so that decoding untrusted data cannot exhaust the stack.
+pub const MAX_ARRAY_DEPTH: usize = 32;
+
+/// Represents a single character in type tag string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TypeTag {
+    /// Nil `N`.
+    Nil,
+
+    /// Infinitum `I`.
+    Infinitum,
+
+    /// Boolean true `T`.
+    True,
+
+    /// Boolean false `F`.
+    False,
+
+    /// ASCII character `c`.
+    Character,
+
+    /// 32bit integer `i`.
+    Int32,
+
+    /// 64bit integer `h`.
+    Int64,
+
+    /// 32bit float-point `f`.
+    Float32,
+
+    /// 64bit float-point `d`.
+    Float64,
+
+    /// Color `r`.
+    Color,
+
+    /// MIDI message `m`.
+    MidiMessage,
+
+    /// OSC time tag `t`.
+    TimeTag,
+
+    /// String `s`.
+    String,
+
+    /// Alternative form of string `S`.
+    Alternative,
+
+    /// Blob `b`.
+    Blob,
+
+    /// Beginning of array `[`.
+    ArrayBegin,
+
+    /// End of array `]`.
+    ArrayEnd,
+}
+
+impl TypeTag {
+    /// Returns the tag of `value`.
+    /// Returns `None` for arrays, which consist of multiple tags.
+    pub fn of(value: &Value) -> Option<TypeTag> {
+        let tag = match value {
+            Value::Nil => TypeTag::Nil,
+            Value::Infinitum => TypeTag::Infinitum,
+            Value::Boolean(true) => TypeTag::True,
+            Value::Boolean(false) => TypeTag::False,
+            Value::Character(_) => TypeTag::Character,
+            Value::Int32(_) => TypeTag::Int32,
+            Value::Int64(_) => TypeTag::Int64,
+            Value::Float32(_) => TypeTag::Float32,
+            Value::Float64(_) => TypeTag::Float64,
+            Value::Color(_) => TypeTag::Color,
+            Value::MidiMessage(_) => TypeTag::MidiMessage,
+            Value::TimeTag(_) => TypeTag::TimeTag,
+            Value::String(_) => TypeTag::String,
+            Value::Alternative(_) => TypeTag::Alternative,
+            Value::Blob(_) => TypeTag::Blob,
+            Value::Array(_) => return None,
+        };
+        Some(tag)
+    }
+
+    /// Returns the tag character.
+    pub fn as_char(self) -> char {
+        match self {
+            TypeTag::Nil => 'N',
+            TypeTag::Infinitum => 'I',
+            TypeTag::True => 'T',
+            TypeTag::False => 'F',
+            TypeTag::Character => 'c',
+            TypeTag::Int32 => 'i',
+            TypeTag::Int64 => 'h',
+            TypeTag::Float32 => 'f',
+            TypeTag::Float64 => 'd',
+            TypeTag::Color => 'r',
+            TypeTag::MidiMessage => 'm',
+            TypeTag::TimeTag => 't',
+            TypeTag::String => 's',
+            TypeTag::Alternative => 'S',
+            TypeTag::Blob => 'b',
+            TypeTag::ArrayBegin => '[',
+            TypeTag::ArrayEnd => ']',
+        }
+    }
+}
+
+impl TryFrom<u8> for TypeTag {
+    type Error = Error;
+
+    fn try_from(tag: u8) -> Result<TypeTag> {
+        let tag = match tag {
+            b'N' => TypeTag::Nil,
+            b'I' => TypeTag::Infinitum,
+            b'T' => TypeTag::True,
+            b'F' => TypeTag::False,
+            b'c' => TypeTag::Character,
+            b'i' => TypeTag::Int32,
+            b'h' => TypeTag::Int64,
+            b'f' => TypeTag::Float32,
+            b'd' => TypeTag::Float64,
+            b'r' => TypeTag::Color,
+            b'm' => TypeTag::MidiMessage,
+            b't' => TypeTag::TimeTag,
+            b's' => TypeTag::String,
+            b'S' => TypeTag::Alternative,
+            b'b' => TypeTag::Blob,
+            b'[' => TypeTag::ArrayBegin,
+            b']' => TypeTag::ArrayEnd,
+            otherwise => return Err(Error::UnknownType(otherwise)),
+        };
+        Ok(tag)
+    }
+}
+
+/// A valid OSC type tag string with leading comma, such as `,iif[ss]`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TypeTags(String);
+
+impl TypeTags {
+    /// Checks validity and wraps into `TypeTags`.
+    /// Every tag must be known and array brackets must be balanced.
+    pub fn new(type_tags: &str) -> Result<TypeTags> {
+        validate_type_tags(type_tags.as_bytes())?;
+        Ok(TypeTags(type_tags.into()))
+    }
+
+    /// Creates type tags describing `values`.
+    pub fn from_values(values: &[Value]) -> TypeTags {
+        let mut type_tags: String = ",".into();
+        for value in values {
+            value.push_type_tag_to(&mut type_tags);
+        }
+        TypeTags(type_tags)
+    }
+
+    /// Returns the type tag string with leading comma.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Iterates over tags, excluding leading comma.
+    pub fn iter(&self) -> impl Iterator<Item = TypeTag> + '_ {
+        self.0
+            .bytes()
+            .skip(1)
+            .map(|b| TypeTag::try_from(b).expect("TypeTags should contain only valid tags"))
+    }
+
+    /// Returns the number of tags, excluding leading comma.
+    pub fn len(&self) -> usize {
+        self.0.len() - 1
+    }
+
+    /// Whether this has no tags.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether `other` is the same signature.
+    /// `T` and `F` are treated as the same type, since they encode values rather than types.
+    pub fn is_compatible(&self, other: &TypeTags) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|(a, b)| a == b || (is_boolean(a) && is_boolean(b)))
+    }
+
+    /// Consume itself and return inner `String`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl AsRef<str> for TypeTags {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<TypeTags> for String {
+    fn from(x: TypeTags) -> Self {
+        x.0
+    }
+}
+
+impl FromStr for TypeTags {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<TypeTags> {
+        TypeTags::new(s)
+    }
+}
+
+impl Display for TypeTags {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.0.fmt(f)
+    }
+}
+
+/// Checks validity of type tag string with leading comma, without allocation.
+/// Returns the number of values, counting an array as one.
+pub(crate) fn validate_type_tags(type_tags: &[u8]) -> Result<usize> {
+    let tags = match type_tags.split_first() {
+        Some((b',', t)) => t,
+        _ => return Err(Error::InvalidTag),
+    };
+
+    let mut depth = 0usize;
+    let mut values = 0;
+    for &tag in tags {
+        match TypeTag::try_from(tag)? {
+            TypeTag::ArrayBegin if depth == MAX_ARRAY_DEPTH => return Err(Error::TooDeep),
+            TypeTag::ArrayBegin => {
+                if depth == 0 {
+                    values += 1;
+                }
+                depth += 1;
+            }
+            TypeTag::ArrayEnd if depth == 0 => return Err(Error::IllegalStructure),
+            TypeTag::ArrayEnd => depth -= 1,
+            _ if depth == 0 => values += 1,
+            _ => (),
+        }
+    }
+    if depth != 0 {
+        return Err(Error::IllegalStructure);
+    }
+
+    Ok(values)
+}
+
+/// Whether `tag` is `T` or `F`.
+fn is_boolean(tag: TypeTag) -> bool {
+    matches!(tag, TypeTag::True | TypeTag::False)
+}
+
+/// Holds expected signatures for each address.
+/// Messages to unregistered addresses are always accepted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SignatureRegistry {
+    signatures: BTreeMap<AddressBuf, TypeTags>,
+}
+
+impl SignatureRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> SignatureRegistry {
+        SignatureRegistry::default()
+    }
+
+    /// Registers expected signature of `address`.
+    /// Returns previously registered one.
+    pub fn register(&mut self, address: &AddressStr, type_tags: TypeTags) -> Option<TypeTags> {
+        self.signatures.insert(address.to_address_buf(), type_tags)
+    }
+
+    /// Removes expected signature of `address`.
+    pub fn unregister(&mut self, address: &AddressStr) -> Option<TypeTags> {
+        self.signatures.remove(address)
+    }
+
+    /// Returns expected signature of `address`.
+    pub fn get(&self, address: &AddressStr) -> Option<&TypeTags> {
+        self.signatures.get(address)
+    }
+
+    /// Iterates over registered signatures in address order.
+    pub fn iter(&self) -> impl Iterator<Item = (&AddressStr, &TypeTags)> {
+        self.signatures.iter().map(|(a, t)| (a.as_address_str(), t))
+    }
+
+    /// Checks whether `message` matches the registered signature.
+    pub fn validate(&self, message: &Message) -> Result<()> {
+        let expected = match self.get(message.address()) {
+            Some(e) => e,
+            None => return Ok(()),
+        };
+
+        let actual = message.type_tags();
+        if expected.is_compatible(&actual) {
+            Ok(())
+        } else {
+            Err(mismatch(message.address(), expected, &actual))
+        }
+    }
+
+    /// Converts arguments of `message` into the registered signature.
+    /// Numbers are converted between integers, floats and booleans, and strings between `s` and `S`.
+    /// Returns an error if any argument cannot be converted.
+    pub fn coerce(&self, message: Message) -> Result<Message> {
+        let expected = match self.get(message.address()) {
+            Some(e) => e,
+            None => return Ok(message),
+        };
+        let actual = message.type_tags();
+        if expected.is_compatible(&actual) {
+            return Ok(message);
+        }
+
+        let mismatch_error = || mismatch(message.address(), expected, &actual);
+        let expected_tags: Vec<_> = expected.iter().collect();
+        if expected_tags.contains(&TypeTag::ArrayBegin)
+            || expected.len() != message.arguments().len()
+        {
+            return Err(mismatch_error());
+        }
+        let arguments = expected_tags
+            .into_iter()
+            .zip(message.arguments())
+            .map(|(tag, value)| coerce_value(tag, value))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(mismatch_error)?;
+
+        Ok(
+            MessageBuilder::from_address(message.address().to_address_buf())
+                .set_arguments(arguments)
+                .build(),
+        )
+    }
+}
+
+impl FromIterator<(AddressBuf, TypeTags)> for SignatureRegistry {
+    fn from_iter<T: IntoIterator<Item = (AddressBuf, TypeTags)>>(iter: T) -> Self {
+        SignatureRegistry {
+            signatures: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Creates signature mismatch error.
+fn mismatch(address: &AddressStr, expected: &TypeTags, actual: &TypeTags) -> Error {
+    Error::SignatureMismatch {
+        address: address.to_string(),
+        expected: expected.to_string(),
+        actual: actual.to_string(),
+    }
+}
+
+/// Converts `value` into the type of `tag`.
+fn coerce_value(tag: TypeTag, value: &Value) -> Option<Value> {
+    let number = match value {
+        Value::Boolean(b) => Some(if *b { 1.0 } else { 0.0 }),
+        Value::Int32(x) => Some(*x as f64),
+        Value::Int64(x) => Some(*x as f64),
+        Value::Float32(x) => Some(*x as f64),
+        Value::Float64(x) => Some(*x),
+        _ => None,
+    };
+
+    let coerced = match (tag, value, number) {
+        (TypeTag::String, Value::String(s) | Value::Alternative(s), _) => Value::String(s.clone()),
+        (TypeTag::Alternative, Value::String(s) | Value::Alternative(s), _) => {
+            Value::Alternative(s.clone())
+        }
+        (TypeTag::True | TypeTag::False, _, Some(x)) => Value::Boolean(x != 0.0),
+        (TypeTag::Int32, _, Some(x)) => Value::Int32(x as i32),
+        (TypeTag::Int64, Value::Int64(x), _) => Value::Int64(*x),
+        (TypeTag::Int64, _, Some(x)) => Value::Int64(x as i64),
+        (TypeTag::Float32, _, Some(x)) => Value::Float32(x as f32),
+        (TypeTag::Float64, _, Some(x)) => Value::Float64(x),
+        (tag, value, _) if TypeTag::of(value) == Some(tag) => value.clone(),
+        _ => return None,
+    };
+    Some(coerced)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SignatureRegistry, TypeTag, TypeTags, MAX_ARRAY_DEPTH};
+    use crate::{
+        address::AddressStr,
+        data::Value,
+        error::Error,
+        packet::{Message, MessageBuilder},
+    };
+
+    use alloc::{format, vec, vec::Vec};
+
+    fn message(arguments: Vec<Value>) -> Message {
+        MessageBuilder::new("/avatar/parameters/Foo")
+            .expect("Should valid")
+            .set_arguments(arguments)
+            .build()
+    }
+
+    /// Ensures type tag strings are validated.
+    #[test]
+    fn test_type_tags_parse() {
+        let type_tags = TypeTags::new(",iif[ss]").expect("Should valid");
+        assert_eq!(type_tags.len(), 7);
+        assert_eq!(
+            type_tags.iter().collect::<Vec<_>>(),
+            vec![
+                TypeTag::Int32,
+                TypeTag::Int32,
+                TypeTag::Float32,
+                TypeTag::ArrayBegin,
+                TypeTag::String,
+                TypeTag::String,
+                TypeTag::ArrayEnd,
+            ]
+        );
+        assert!(TypeTags::new(",").expect("Should valid").is_empty());
+
+        assert_eq!(TypeTags::new("iif"), Err(Error::InvalidTag));
+        assert_eq!(TypeTags::new(",ix"), Err(Error::UnknownType(b'x')));
+        assert_eq!(TypeTags::new(",[i"), Err(Error::IllegalStructure));
+        assert_eq!(TypeTags::new(",i]["), Err(Error::IllegalStructure));
+
+        let deepest = format!(
+            ",{}{}",
+            "[".repeat(MAX_ARRAY_DEPTH),
+            "]".repeat(MAX_ARRAY_DEPTH)
+        );
+        assert!(TypeTags::new(&deepest).is_ok());
+        let too_deep = format!(",[{}]", &deepest[1..]);
+        assert_eq!(TypeTags::new(&too_deep), Err(Error::TooDeep));
+    }
+
+    /// Ensures type tags are derived from values.
+    #[test]
+    fn test_type_tags_from_values() {
+        let values = [
+            Value::Int32(1),
+            Value::Boolean(false),
+            Value::Array(vec![Value::String("a".into())]),
+        ];
+        assert_eq!(TypeTags::from_values(&values).as_str(), ",iF[s]");
+        assert_eq!(message(values.to_vec()).type_tags().as_str(), ",iF[s]");
+    }
+
+    /// Ensures registered signatures reject mismatched messages.
+    #[test]
+    fn test_registry_validate() {
+        let mut registry = SignatureRegistry::new();
+        registry.register(
+            AddressStr::new("/avatar/parameters/Foo").expect("Should valid"),
+            ",T".parse().expect("Should valid"),
+        );
+
+        assert!(registry
+            .validate(&message(vec![Value::Boolean(false)]))
+            .is_ok());
+        assert_eq!(
+            registry.validate(&message(vec![Value::Float32(0.5)])),
+            Err(Error::SignatureMismatch {
+                address: "/avatar/parameters/Foo".into(),
+                expected: ",T".into(),
+                actual: ",f".into(),
+            })
+        );
+
+        let unregistered = MessageBuilder::new("/avatar/change")
+            .expect("Should valid")
+            .push_argument(Value::Int32(1))
+            .build();
+        assert!(registry.validate(&unregistered).is_ok());
+    }
+
+    /// Ensures arguments are coerced into registered signatures.
+    #[test]
+    fn test_registry_coerce() {
+        let registry: SignatureRegistry = vec![(
+            "/avatar/parameters/Foo".parse().expect("Should valid"),
+            ",fiT".parse().expect("Should valid"),
+        )]
+        .into_iter()
+        .collect();
+
+        let coerced = registry
+            .coerce(message(vec![
+                Value::Int32(1),
+                Value::Float64(2.5),
+                Value::Int32(0),
+            ]))
+            .expect("Should coerce");
+        assert_eq!(
+            coerced.arguments(),
+            &[Value::Float32(1.0), Value::Int32(2), Value::Boolean(false)]
+        );
+
+        assert!(matches!(
+            registry.coerce(message(vec![Value::String("a".into())])),
+            Err(Error::SignatureMismatch { .. })
+        ));
+    }
+}
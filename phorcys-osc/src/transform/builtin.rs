@@ -0,0 +1,498 @@
+//! Built-in transforms.
+//!
+//! Argument transforms take optional address pattern as scope,
+//! and affect only messages whose address matches it.
+
+use crate::{
+    address::{AddressBuf, AddressPattern},
+    data::Value,
+    packet::{Message, MessageBuilder},
+    signature::TypeTag,
+    transform::{Transform, TransformError, TransformResult},
+};
+
+use regex::Regex;
+
+/// Address pattern which argument transforms apply to.
+/// `None` matches all addresses.
+#[derive(Debug, Clone, Default)]
+struct Scope(Option<Regex>);
+
+impl Scope {
+    fn new(pattern: &str) -> TransformResult<Scope> {
+        Ok(Scope(Some(AddressPattern::compile_to_regex(pattern)?)))
+    }
+
+    fn matches(&self, message: &Message) -> bool {
+        match &self.0 {
+            Some(regex) => regex.is_match(message.address().as_str()),
+            None => true,
+        }
+    }
+}
+
+/// Applies `f` to each argument of the message in scope, including elements of arrays.
+fn map_scalars(scope: &Scope, message: Message, f: impl Fn(Value) -> Value) -> Message {
+    fn map_value(value: Value, f: &impl Fn(Value) -> Value) -> Value {
+        match value {
+            Value::Array(values) => {
+                Value::Array(values.into_iter().map(|v| map_value(v, f)).collect())
+            }
+            otherwise => f(otherwise),
+        }
+    }
+
+    if !scope.matches(&message) {
+        return message;
+    }
+    let (address, arguments) = message.into_parts();
+    MessageBuilder::from_address(address)
+        .set_arguments(
+            arguments
+                .into_iter()
+                .map(|v| map_value(v, &f))
+                .collect::<Vec<_>>(),
+        )
+        .build()
+}
+
+/// Passes or drops messages by address pattern.
+#[derive(Debug, Clone)]
+pub struct Filter {
+    regex: Regex,
+    keep: bool,
+}
+
+impl Filter {
+    /// Keeps only messages matching the pattern.
+    pub fn keep(pattern: &str) -> TransformResult<Filter> {
+        Ok(Filter {
+            regex: AddressPattern::compile_to_regex(pattern)?,
+            keep: true,
+        })
+    }
+
+    /// Drops messages matching the pattern.
+    pub fn drop(pattern: &str) -> TransformResult<Filter> {
+        Ok(Filter {
+            regex: AddressPattern::compile_to_regex(pattern)?,
+            keep: false,
+        })
+    }
+}
+
+impl Transform for Filter {
+    fn transform_message(&self, message: Message) -> Option<Message> {
+        if self.regex.is_match(message.address().as_str()) == self.keep {
+            Some(message)
+        } else {
+            None
+        }
+    }
+}
+
+/// Rewrites addresses matching the pattern.
+///
+/// Each non-literal expression in the pattern is captured,
+/// and the replacement refers them by `$1`, `$2`, ... (or `${1}` followed by method characters).
+/// Messages are left untouched when the rewritten address is invalid.
+#[derive(Debug, Clone)]
+pub struct Rewrite {
+    regex: Regex,
+    replacement: String,
+}
+
+impl Rewrite {
+    /// Creates a rewrite from the pattern to the replacement.
+    pub fn new(pattern: &str, replacement: &str) -> TransformResult<Rewrite> {
+        Ok(Rewrite {
+            regex: AddressPattern::compile_to_capturing_regex(pattern)?,
+            replacement: replacement.into(),
+        })
+    }
+}
+
+impl Transform for Rewrite {
+    fn transform_message(&self, message: Message) -> Option<Message> {
+        if !self.regex.is_match(message.address().as_str()) {
+            return Some(message);
+        }
+
+        let rewritten = self
+            .regex
+            .replace(message.address().as_str(), self.replacement.as_str());
+        match AddressBuf::new(&rewritten) {
+            Ok(address) => {
+                let (_, arguments) = message.into_parts();
+                Some(
+                    MessageBuilder::from_address(address)
+                        .set_arguments(arguments)
+                        .build(),
+                )
+            }
+            Err(_) => Some(message),
+        }
+    }
+}
+
+/// Maps arguments with arbitrary function.
+#[derive(Debug, Clone)]
+pub struct MapArguments<F> {
+    scope: Scope,
+    map: F,
+}
+
+impl<F: Fn(Value) -> Value + Send + Sync> MapArguments<F> {
+    /// Creates a transform which maps every argument with `map`.
+    /// Elements of arrays are passed individually.
+    pub fn new(map: F) -> MapArguments<F> {
+        MapArguments {
+            scope: Scope::default(),
+            map,
+        }
+    }
+
+    /// Limits the scope to messages matching the pattern.
+    pub fn with_pattern(mut self, pattern: &str) -> TransformResult<Self> {
+        self.scope = Scope::new(pattern)?;
+        Ok(self)
+    }
+}
+
+impl<F: Fn(Value) -> Value + Send + Sync> Transform for MapArguments<F> {
+    fn transform_message(&self, message: Message) -> Option<Message> {
+        Some(map_scalars(&self.scope, message, &self.map))
+    }
+}
+
+/// Scales float arguments by `x * factor + offset`.
+#[derive(Debug, Clone)]
+pub struct Scale {
+    scope: Scope,
+    factor: f64,
+    offset: f64,
+}
+
+impl Scale {
+    /// Creates a scale transform.
+    pub fn new(factor: f64, offset: f64) -> Scale {
+        Scale {
+            scope: Scope::default(),
+            factor,
+            offset,
+        }
+    }
+
+    /// Limits the scope to messages matching the pattern.
+    pub fn with_pattern(mut self, pattern: &str) -> TransformResult<Self> {
+        self.scope = Scope::new(pattern)?;
+        Ok(self)
+    }
+}
+
+impl Transform for Scale {
+    fn transform_message(&self, message: Message) -> Option<Message> {
+        Some(map_scalars(&self.scope, message, |v| match v {
+            Value::Float32(x) => Value::Float32((x as f64 * self.factor + self.offset) as f32),
+            Value::Float64(x) => Value::Float64(x * self.factor + self.offset),
+            otherwise => otherwise,
+        }))
+    }
+}
+
+/// Clamps integer and float arguments into `[min, max]`.
+/// Integers are clamped into the integers within the range without going through floats.
+#[derive(Debug, Clone)]
+pub struct Clamp {
+    scope: Scope,
+    min: f64,
+    max: f64,
+}
+
+impl Clamp {
+    /// Creates a clamp transform.
+    /// Fails if `min > max` or either is `NaN`.
+    pub fn new(min: f64, max: f64) -> TransformResult<Clamp> {
+        if min.is_nan() || max.is_nan() || min > max {
+            return Err(TransformError::InvalidRange { min, max });
+        }
+        Ok(Clamp {
+            scope: Scope::default(),
+            min,
+            max,
+        })
+    }
+
+    /// Limits the scope to messages matching the pattern.
+    pub fn with_pattern(mut self, pattern: &str) -> TransformResult<Self> {
+        self.scope = Scope::new(pattern)?;
+        Ok(self)
+    }
+
+    /// Clamps the integer into integral bounds of the range.
+    /// If no integer lies in the range, the integers just around it are used as bounds.
+    fn clamp_integer(&self, x: i64) -> i64 {
+        let (low, high) = (self.min.ceil() as i64, self.max.floor() as i64);
+        x.clamp(low.min(high), high.max(low))
+    }
+}
+
+impl Transform for Clamp {
+    fn transform_message(&self, message: Message) -> Option<Message> {
+        Some(map_scalars(&self.scope, message, |v| match v {
+            Value::Int32(x) => {
+                let clamped = self.clamp_integer(x.into());
+                Value::Int32(clamped.clamp(i32::MIN.into(), i32::MAX.into()) as i32)
+            }
+            Value::Int64(x) => Value::Int64(self.clamp_integer(x)),
+            Value::Float32(x) => Value::Float32((x as f64).clamp(self.min, self.max) as f32),
+            Value::Float64(x) => Value::Float64(x.clamp(self.min, self.max)),
+            otherwise => otherwise,
+        }))
+    }
+}
+
+/// Casts numeric and boolean arguments into another type.
+/// Conversions follow `Value::as_f32`, `Value::as_i32` and `Value::as_bool`,
+/// except that conversions into `Int64` are not limited to `i32` range.
+#[derive(Debug, Clone)]
+pub struct Cast {
+    scope: Scope,
+    to: TypeTag,
+}
+
+impl Cast {
+    /// Creates a cast transform.
+    /// Available targets are `i`, `h`, `f`, `d` and `T` (`F` means the same).
+    pub fn new(to: TypeTag) -> TransformResult<Cast> {
+        match to {
+            TypeTag::Int32
+            | TypeTag::Int64
+            | TypeTag::Float32
+            | TypeTag::Float64
+            | TypeTag::True
+            | TypeTag::False => Ok(Cast {
+                scope: Scope::default(),
+                to,
+            }),
+            otherwise => Err(TransformError::InvalidCast(otherwise)),
+        }
+    }
+
+    /// Limits the scope to messages matching the pattern.
+    pub fn with_pattern(mut self, pattern: &str) -> TransformResult<Self> {
+        self.scope = Scope::new(pattern)?;
+        Ok(self)
+    }
+
+    fn cast(&self, value: Value) -> Value {
+        let casted = match self.to {
+            TypeTag::Int32 => value.as_i32().map(Value::Int32),
+            TypeTag::Int64 => match value {
+                Value::Int32(x) => Some(Value::Int64(x.into())),
+                Value::Int64(x) => Some(Value::Int64(x)),
+                Value::Float32(x) => Some(Value::Int64(x as i64)),
+                Value::Float64(x) => Some(Value::Int64(x as i64)),
+                Value::Boolean(b) => Some(Value::Int64(b.into())),
+                _ => None,
+            },
+            TypeTag::Float32 => value.as_f32().map(Value::Float32),
+            TypeTag::Float64 => match value {
+                Value::Float64(x) => Some(Value::Float64(x)),
+                Value::Int32(x) => Some(Value::Float64(x as f64)),
+                Value::Int64(x) => Some(Value::Float64(x as f64)),
+                ref otherwise => otherwise.as_f32().map(|x| Value::Float64(x as f64)),
+            },
+            _ => value.as_bool().map(Value::Boolean),
+        };
+        casted.unwrap_or(value)
+    }
+}
+
+impl Transform for Cast {
+    fn transform_message(&self, message: Message) -> Option<Message> {
+        Some(map_scalars(&self.scope, message, |v| self.cast(v)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Cast, Clamp, Filter, MapArguments, Rewrite, Scale};
+    use crate::{
+        data::Value,
+        packet::{Message, MessageBuilder},
+        signature::TypeTag,
+        transform::{Transform, TransformError},
+    };
+
+    fn message(address: &str, arguments: Vec<Value>) -> Message {
+        MessageBuilder::new(address)
+            .expect("Should valid")
+            .set_arguments(arguments)
+            .build()
+    }
+
+    /// Ensures filters keep or drop messages by pattern.
+    #[test]
+    fn test_filter() {
+        let keep = Filter::keep("/avatar/parameters/*").expect("Should valid");
+        let drop = Filter::drop("/avatar/parameters/*").expect("Should valid");
+        let matching = message("/avatar/parameters/Foo", vec![]);
+        let other = message("/input/Jump", vec![]);
+
+        assert_eq!(
+            keep.transform_message(matching.clone()),
+            Some(matching.clone())
+        );
+        assert_eq!(keep.transform_message(other.clone()), None);
+        assert_eq!(drop.transform_message(matching), None);
+        assert_eq!(drop.transform_message(other.clone()), Some(other));
+    }
+
+    /// Ensures rewrites substitute captures and keep arguments.
+    #[test]
+    fn test_rewrite() {
+        let rewrite = Rewrite::new("/avatar/parameters/*", "/params/$1").expect("Should valid");
+        assert_eq!(
+            rewrite.transform_message(message("/avatar/parameters/Foo", vec![Value::Int32(1)])),
+            Some(message("/params/Foo", vec![Value::Int32(1)]))
+        );
+        assert_eq!(
+            rewrite.transform_message(message("/input/Jump", vec![])),
+            Some(message("/input/Jump", vec![]))
+        );
+
+        let invalid = Rewrite::new("/foo/*", "bar/$1").expect("Should valid");
+        assert_eq!(
+            invalid.transform_message(message("/foo/baz", vec![])),
+            Some(message("/foo/baz", vec![]))
+        );
+    }
+
+    /// Ensures argument transforms respect the scope and recurse into arrays.
+    #[test]
+    fn test_argument_transforms() {
+        let scale = Scale::new(0.5, 1.0)
+            .with_pattern("/scaled/*")
+            .expect("Should valid");
+        assert_eq!(
+            scale.transform_message(message(
+                "/scaled/foo",
+                vec![
+                    Value::Float32(2.0),
+                    Value::Array(vec![Value::Float64(4.0)]),
+                    Value::Int32(2)
+                ]
+            )),
+            Some(message(
+                "/scaled/foo",
+                vec![
+                    Value::Float32(2.0),
+                    Value::Array(vec![Value::Float64(3.0)]),
+                    Value::Int32(2)
+                ]
+            ))
+        );
+        assert_eq!(
+            scale.transform_message(message("/other", vec![Value::Float32(2.0)])),
+            Some(message("/other", vec![Value::Float32(2.0)]))
+        );
+
+        let clamp = Clamp::new(-1.0, 1.0).expect("Should valid");
+        assert_eq!(
+            clamp.transform_message(message(
+                "/foo",
+                vec![Value::Float32(1.5), Value::Int32(-3), Value::Int64(0)]
+            )),
+            Some(message(
+                "/foo",
+                vec![Value::Float32(1.0), Value::Int32(-1), Value::Int64(0)]
+            ))
+        );
+
+        // Integers are not rounded through floats.
+        let clamp = Clamp::new(-1.0, 1e19).expect("Should valid");
+        assert_eq!(
+            clamp.transform_message(message(
+                "/foo",
+                vec![Value::Int64(i64::MAX - 1), Value::Int32(i32::MAX)]
+            )),
+            Some(message(
+                "/foo",
+                vec![Value::Int64(i64::MAX - 1), Value::Int32(i32::MAX)]
+            ))
+        );
+        let clamp = Clamp::new(0.2, 0.8).expect("Should valid");
+        assert_eq!(
+            clamp.transform_message(message("/foo", vec![Value::Int32(-5), Value::Int32(5)])),
+            Some(message("/foo", vec![Value::Int32(0), Value::Int32(1)]))
+        );
+
+        let negate = MapArguments::new(|v| match v {
+            Value::Boolean(b) => Value::Boolean(!b),
+            otherwise => otherwise,
+        });
+        assert_eq!(
+            negate.transform_message(message("/foo", vec![Value::Boolean(true)])),
+            Some(message("/foo", vec![Value::Boolean(false)]))
+        );
+    }
+
+    /// Ensures casts convert numbers and booleans and reject other targets.
+    #[test]
+    fn test_cast() {
+        let to_float = Cast::new(TypeTag::Float32).expect("Should valid");
+        assert_eq!(
+            to_float.transform_message(message(
+                "/foo",
+                vec![
+                    Value::Int32(1),
+                    Value::Boolean(true),
+                    Value::String("x".into())
+                ]
+            )),
+            Some(message(
+                "/foo",
+                vec![
+                    Value::Float32(1.0),
+                    Value::Float32(1.0),
+                    Value::String("x".into())
+                ]
+            ))
+        );
+
+        let to_long = Cast::new(TypeTag::Int64).expect("Should valid");
+        assert_eq!(
+            to_long.transform_message(message(
+                "/foo",
+                vec![Value::Float64(5e9), Value::Int32(-1), Value::Boolean(true)]
+            )),
+            Some(message(
+                "/foo",
+                vec![
+                    Value::Int64(5_000_000_000),
+                    Value::Int64(-1),
+                    Value::Int64(1)
+                ]
+            ))
+        );
+
+        let to_bool = Cast::new(TypeTag::True).expect("Should valid");
+        assert_eq!(
+            to_bool.transform_message(message("/foo", vec![Value::Float32(0.0), Value::Int64(2)])),
+            Some(message(
+                "/foo",
+                vec![Value::Boolean(false), Value::Boolean(true)]
+            ))
+        );
+
+        assert!(matches!(
+            Clamp::new(1.0, -1.0),
+            Err(TransformError::InvalidRange { .. })
+        ));
+        assert!(matches!(
+            Cast::new(TypeTag::String),
+            Err(TransformError::InvalidCast(TypeTag::String))
+        ));
+    }
+}
@@ -0,0 +1,213 @@
+//! Declarative description of pipelines.
+//!
+//! A pipeline is described as an array of tables named `transform`:
+//!
+//! ```toml
+//! [[transform]]
+//! type = "filter"
+//! action = "drop"
+//! pattern = "/avatar/parameters/VelocityX"
+//!
+//! [[transform]]
+//! type = "rewrite"
+//! pattern = "/avatar/parameters/*"
+//! replacement = "/params/$1"
+//!
+//! [[transform]]
+//! type = "clamp"
+//! pattern = "/params/*"
+//! min = 0.0
+//! max = 1.0
+//! ```
+
+use crate::{
+    signature::TypeTag,
+    transform::{Cast, Clamp, Filter, Pipeline, Rewrite, Scale, TransformResult},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Whether `filter` keeps or drops matching messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterAction {
+    /// Keeps only matching messages.
+    Keep,
+
+    /// Drops matching messages.
+    Drop,
+}
+
+/// Description of a built-in transform.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransformDescription {
+    /// `Filter`.
+    Filter {
+        action: FilterAction,
+        pattern: String,
+    },
+
+    /// `Rewrite`.
+    Rewrite {
+        pattern: String,
+        replacement: String,
+    },
+
+    /// `Scale`.
+    Scale {
+        pattern: Option<String>,
+        #[serde(default = "default_factor")]
+        factor: f64,
+        #[serde(default)]
+        offset: f64,
+    },
+
+    /// `Clamp`.
+    Clamp {
+        pattern: Option<String>,
+        min: f64,
+        max: f64,
+    },
+
+    /// `Cast`, where `to` is a type tag character.
+    Cast { pattern: Option<String>, to: char },
+}
+
+fn default_factor() -> f64 {
+    1.0
+}
+
+/// Description of `Pipeline`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PipelineDescription {
+    /// Transforms applied in order.
+    #[serde(rename = "transform", default)]
+    pub transforms: Vec<TransformDescription>,
+}
+
+impl PipelineDescription {
+    /// Builds the described pipeline.
+    pub fn build(&self) -> TransformResult<Pipeline> {
+        let mut pipeline = Pipeline::new();
+        for description in &self.transforms {
+            pipeline = match description {
+                TransformDescription::Filter {
+                    action: FilterAction::Keep,
+                    pattern,
+                } => pipeline.push(Filter::keep(pattern)?),
+                TransformDescription::Filter {
+                    action: FilterAction::Drop,
+                    pattern,
+                } => pipeline.push(Filter::drop(pattern)?),
+                TransformDescription::Rewrite {
+                    pattern,
+                    replacement,
+                } => pipeline.push(Rewrite::new(pattern, replacement)?),
+                TransformDescription::Scale {
+                    pattern,
+                    factor,
+                    offset,
+                } => {
+                    let scale = Scale::new(*factor, *offset);
+                    match pattern {
+                        Some(p) => pipeline.push(scale.with_pattern(p)?),
+                        None => pipeline.push(scale),
+                    }
+                }
+                TransformDescription::Clamp { pattern, min, max } => {
+                    let clamp = Clamp::new(*min, *max)?;
+                    match pattern {
+                        Some(p) => pipeline.push(clamp.with_pattern(p)?),
+                        None => pipeline.push(clamp),
+                    }
+                }
+                TransformDescription::Cast { pattern, to } => {
+                    // Non-ASCII characters are reported as unknown NUL type.
+                    let tag = TypeTag::try_from(u8::try_from(*to).unwrap_or(0))?;
+                    let cast = Cast::new(tag)?;
+                    match pattern {
+                        Some(p) => pipeline.push(cast.with_pattern(p)?),
+                        None => pipeline.push(cast),
+                    }
+                }
+            };
+        }
+        Ok(pipeline)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::Value,
+        packet::{MessageBuilder, Packet},
+        transform::{Pipeline, Transform, TransformError},
+    };
+
+    /// Ensures a pipeline loaded from TOML applies transforms in order.
+    #[test]
+    fn test_pipeline_from_toml() {
+        let pipeline = Pipeline::from_toml(
+            r#"
+            [[transform]]
+            type = "filter"
+            action = "drop"
+            pattern = "/avatar/parameters/VelocityX"
+
+            [[transform]]
+            type = "rewrite"
+            pattern = "/avatar/parameters/*"
+            replacement = "/params/$1"
+
+            [[transform]]
+            type = "clamp"
+            pattern = "/params/*"
+            min = 0.0
+            max = 1.0
+
+            [[transform]]
+            type = "cast"
+            to = "i"
+            "#,
+        )
+        .expect("Should success");
+        assert_eq!(pipeline.len(), 4);
+
+        let packet = |address: &str, value: Value| {
+            Packet::Message(
+                MessageBuilder::new(address)
+                    .expect("Should valid")
+                    .push_argument(value)
+                    .build(),
+            )
+        };
+        assert_eq!(
+            pipeline.transform(packet("/avatar/parameters/VelocityX", Value::Float32(1.0))),
+            None
+        );
+        assert_eq!(
+            pipeline.transform(packet("/avatar/parameters/Foo", Value::Float32(2.5))),
+            Some(packet("/params/Foo", Value::Int32(1)))
+        );
+    }
+
+    /// Ensures invalid descriptions are reported.
+    #[test]
+    fn test_pipeline_from_toml_invalid() {
+        assert!(matches!(
+            Pipeline::from_toml("[[transform]]\ntype = \"unknown\""),
+            Err(TransformError::Description(_))
+        ));
+        assert!(matches!(
+            Pipeline::from_toml("[[transform]]\ntype = \"cast\"\nto = \"s\""),
+            Err(TransformError::InvalidCast(_))
+        ));
+        assert!(matches!(
+            Pipeline::from_toml(
+                "[[transform]]\ntype = \"filter\"\naction = \"keep\"\npattern = \"/foo/[\""
+            ),
+            Err(TransformError::Osc(_))
+        ));
+    }
+}
@@ -0,0 +1,196 @@
+//! Composable transforms of OSC packets.
+//!
+//! Transforms are chained with `Transform::then` or collected into `Pipeline`,
+//! which can also be loaded from TOML description.
+
+pub mod builtin;
+pub mod description;
+
+pub use crate::transform::{
+    builtin::{Cast, Clamp, Filter, MapArguments, Rewrite, Scale},
+    description::{FilterAction, PipelineDescription, TransformDescription},
+};
+
+use crate::{
+    packet::{Bundle, Message, Packet},
+    signature::TypeTag,
+};
+
+use std::{
+    fmt::{Debug, Formatter, Result as FmtResult},
+    result::Result as StdResult,
+};
+
+use thiserror::Error as ThisError;
+
+/// Represents an error in building transforms.
+#[non_exhaustive]
+#[derive(Debug, ThisError)]
+pub enum TransformError {
+    /// Invalid address pattern.
+    #[error("OSC error: {0}")]
+    Osc(#[from] crate::error::Error),
+
+    /// Type tag which arguments cannot be cast into.
+    #[error("Cannot cast into {0:?}")]
+    InvalidCast(TypeTag),
+
+    /// Empty or `NaN` range.
+    #[error("Invalid range: [{min}, {max}]")]
+    InvalidRange { min: f64, max: f64 },
+
+    /// Invalid description.
+    #[error("Invalid description: {0}")]
+    Description(#[from] toml::de::Error),
+}
+
+/// Result type shorthand with `TransformError`.
+pub type TransformResult<T> = StdResult<T, TransformError>;
+
+/// Transforms OSC packets.
+pub trait Transform: Send + Sync {
+    /// Transforms a message.
+    /// Returns `None` to drop it.
+    fn transform_message(&self, message: Message) -> Option<Message>;
+
+    /// Transforms a packet.
+    /// Elements of bundles are transformed recursively, and the bundle itself is kept even if it gets empty.
+    fn transform(&self, packet: Packet) -> Option<Packet> {
+        match packet {
+            Packet::Message(m) => self.transform_message(m).map(Packet::Message),
+            Packet::Bundle(b) => {
+                let (time_tag, elements) = b.split_into();
+                let elements = elements
+                    .into_iter()
+                    .filter_map(|e| self.transform(e))
+                    .collect();
+                Some(Packet::Bundle(Bundle::new(time_tag, elements)))
+            }
+        }
+    }
+
+    /// Chains `next` after this transform.
+    fn then<T: Transform>(self, next: T) -> Chain<Self, T>
+    where
+        Self: Sized,
+    {
+        Chain {
+            first: self,
+            second: next,
+        }
+    }
+}
+
+/// Two transforms applied in order.
+#[derive(Debug, Clone)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Transform, B: Transform> Transform for Chain<A, B> {
+    fn transform_message(&self, message: Message) -> Option<Message> {
+        self.first
+            .transform_message(message)
+            .and_then(|m| self.second.transform_message(m))
+    }
+}
+
+/// Sequence of transforms applied in order.
+#[derive(Default)]
+pub struct Pipeline {
+    transforms: Vec<Box<dyn Transform>>,
+}
+
+impl Pipeline {
+    /// Creates an empty pipeline, which passes all packets.
+    pub fn new() -> Pipeline {
+        Pipeline::default()
+    }
+
+    /// Appends a transform.
+    pub fn push(mut self, transform: impl Transform + 'static) -> Self {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+
+    /// Returns the number of transforms.
+    pub fn len(&self) -> usize {
+        self.transforms.len()
+    }
+
+    /// Whether this pipeline has no transforms.
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    /// Builds a pipeline from TOML description.
+    pub fn from_toml(source: &str) -> TransformResult<Pipeline> {
+        let description: PipelineDescription = toml::from_str(source)?;
+        description.build()
+    }
+}
+
+impl Debug for Pipeline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("Pipeline")
+            .field("transforms", &self.transforms.len())
+            .finish()
+    }
+}
+
+impl Transform for Pipeline {
+    fn transform_message(&self, message: Message) -> Option<Message> {
+        self.transforms
+            .iter()
+            .try_fold(message, |m, t| t.transform_message(m))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Filter, Pipeline, Scale, Transform};
+    use crate::{
+        data::{TimeTag, Value},
+        packet::{Bundle, MessageBuilder, Packet},
+    };
+
+    fn packet(address: &str, value: f32) -> Packet {
+        Packet::Message(
+            MessageBuilder::new(address)
+                .expect("Should valid")
+                .push_argument(Value::Float32(value))
+                .build(),
+        )
+    }
+
+    /// Ensures chained transforms apply in order and recurse into bundles.
+    #[test]
+    fn test_transform_chain() {
+        let chain = Filter::drop("/debug/*")
+            .expect("Should valid")
+            .then(Scale::new(2.0, 0.0));
+        let bundle = Packet::Bundle(Bundle::new(
+            TimeTag(1),
+            vec![packet("/debug/foo", 1.0), packet("/foo", 0.25)],
+        ));
+
+        let transformed = chain.transform(bundle).expect("Should keep bundle");
+        assert_eq!(
+            transformed,
+            Packet::Bundle(Bundle::new(TimeTag(1), vec![packet("/foo", 0.5)]))
+        );
+        assert_eq!(chain.transform(packet("/debug/bar", 1.0)), None);
+    }
+
+    /// Ensures an empty pipeline passes packets through.
+    #[test]
+    fn test_pipeline_empty() {
+        let pipeline = Pipeline::new();
+        assert!(pipeline.is_empty());
+        assert_eq!(
+            pipeline.transform(packet("/foo", 1.0)),
+            Some(packet("/foo", 1.0))
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! OSCQuery HTTP client.
+
+use crate::namespace::{HostInfo, Node};
+
+use std::result::Result as StdResult;
+
+use async_std::net::{SocketAddr, TcpStream};
+use http_types::{Method, Request, StatusCode, Url};
+use phorcys_osc::data::Value;
+use serde::de::DeserializeOwned;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use thiserror::Error as ThisError;
+
+/// Represents an error in OSCQuery client.
+#[non_exhaustive]
+#[derive(Debug, ThisError)]
+pub enum ClientError {
+    /// Connection error.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// HTTP protocol error.
+    #[error("HTTP error: {0}")]
+    Http(String),
+
+    /// Server responded with unexpected status.
+    #[error("Unexpected status: {0}")]
+    Status(u16),
+
+    /// Response is not a valid OSCQuery JSON.
+    #[error("Invalid response: {0}")]
+    InvalidResponse(#[from] serde_json::Error),
+}
+
+/// Result type shorthand with `ClientError`.
+pub type ClientResult<T> = StdResult<T, ClientError>;
+
+/// Queries OSCQuery server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OscQueryClient {
+    address: SocketAddr,
+}
+
+impl OscQueryClient {
+    /// Creates a client for the server at `address`.
+    pub fn new(address: SocketAddr) -> OscQueryClient {
+        OscQueryClient { address }
+    }
+
+    /// Returns the server address.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Fetches host information.
+    pub async fn host_info(&self) -> ClientResult<HostInfo> {
+        self.get_json("/", Some("HOST_INFO"))
+            .await?
+            .ok_or(ClientError::Status(StatusCode::NoContent as u16))
+    }
+
+    /// Fetches the whole namespace.
+    pub async fn namespace(&self) -> ClientResult<Node> {
+        self.get_json("/", None)
+            .await?
+            .ok_or(ClientError::Status(StatusCode::NoContent as u16))
+    }
+
+    /// Fetches the node at `path`.
+    /// Returns `None` if the node does not exist.
+    pub async fn node(&self, path: &str) -> ClientResult<Option<Node>> {
+        match self.get_json(path, None).await {
+            Err(ClientError::Status(404)) => Ok(None),
+            otherwise => otherwise,
+        }
+    }
+
+    /// Fetches current values of the method at `path`, converted by its type tags.
+    /// Returns `None` if the node does not exist or has no value.
+    pub async fn value(&self, path: &str) -> ClientResult<Option<Vec<Value>>> {
+        let node = match self.node(path).await? {
+            Some(n) => n,
+            None => return Ok(None),
+        };
+        let mut attribute: JsonMap<String, JsonValue> =
+            match self.get_json(path, Some("VALUE")).await {
+                Ok(Some(v)) => v,
+                Ok(None) | Err(ClientError::Status(404)) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+
+        let values = match attribute.remove("VALUE") {
+            Some(JsonValue::Array(values)) => values,
+            _ => return Ok(None),
+        };
+        Ok(Node {
+            value: Some(values),
+            ..node
+        }
+        .values())
+    }
+
+    /// Sends GET request and parses the JSON response.
+    /// Returns `None` for `204 No Content`.
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: Option<&str>,
+    ) -> ClientResult<Option<T>> {
+        let mut url = Url::parse(&format!("http://{}/", self.address))
+            .map_err(|e| ClientError::Http(e.to_string()))?;
+        url.set_path(path);
+        url.set_query(query);
+
+        let stream = TcpStream::connect(self.address).await?;
+        let request = Request::new(Method::Get, url);
+        let mut response = async_h1::connect(stream, request)
+            .await
+            .map_err(|e| ClientError::Http(e.to_string()))?;
+
+        match response.status() {
+            StatusCode::Ok => {
+                let body = response
+                    .body_string()
+                    .await
+                    .map_err(|e| ClientError::Http(e.to_string()))?;
+                Ok(Some(serde_json::from_str(&body)?))
+            }
+            StatusCode::NoContent => Ok(None),
+            status => Err(ClientError::Status(status as u16)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::OscQueryClient;
+    use crate::{
+        namespace::{Access, HostInfo, Node},
+        server::OscQueryServer,
+    };
+
+    use async_std::{net::TcpListener, task::spawn};
+    use phorcys_osc::{address::Address, data::Value};
+
+    /// Ensures the client browses a local server.
+    #[async_std::test]
+    async fn test_client_browse() {
+        let mut root = Node::root();
+        root.insert(
+            &Address::new("/avatar/parameters/Foo").expect("Should valid"),
+            Node::method("f", Access::ReadWrite).with_value(&[Value::Float32(0.5)]),
+        );
+        root.insert(
+            &Address::new("/avatar/change").expect("Should valid"),
+            Node::method("s", Access::Read),
+        );
+        let server = OscQueryServer::new(
+            HostInfo::new(
+                "phorcys-test",
+                "127.0.0.1:9000".parse().expect("Should valid"),
+            ),
+            root,
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Should bind");
+        let client = OscQueryClient::new(listener.local_addr().expect("Should have address"));
+        spawn(server.listen(listener));
+
+        let host_info = client.host_info().await.expect("Should success");
+        assert_eq!(host_info.name, "phorcys-test");
+        assert_eq!(host_info.osc_port, Some(9000));
+
+        let namespace = client.namespace().await.expect("Should success");
+        assert_eq!(
+            namespace.signatures(),
+            vec![
+                (
+                    Address::new("/avatar/change").expect("Should valid"),
+                    ",s".parse().expect("Should valid")
+                ),
+                (
+                    Address::new("/avatar/parameters/Foo").expect("Should valid"),
+                    ",f".parse().expect("Should valid")
+                ),
+            ]
+        );
+
+        let value = client
+            .value("/avatar/parameters/Foo")
+            .await
+            .expect("Should success");
+        assert_eq!(value, Some(vec![Value::Float32(0.5)]));
+        assert_eq!(
+            client
+                .value("/avatar/change")
+                .await
+                .expect("Should success"),
+            None
+        );
+        assert_eq!(
+            client
+                .node("/avatar/unknown")
+                .await
+                .expect("Should success"),
+            None
+        );
+    }
+}
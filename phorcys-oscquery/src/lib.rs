@@ -0,0 +1,19 @@
+//! OSCQuery protocol implementation.
+
+pub mod client;
+pub mod mdns;
+pub mod namespace;
+pub mod server;
+
+/// Prelude module, prefixed with `OscQuery` to avoid identifier conflict.
+pub mod prelude {
+    pub use crate::client::OscQueryClient;
+    pub use crate::mdns::{
+        MdnsBrowser as OscQueryMdnsBrowser, MdnsResponder as OscQueryMdnsResponder,
+    };
+    pub use crate::namespace::{
+        Access as OscQueryAccess, HostInfo as OscQueryHostInfo, Node as OscQueryNode,
+        Range as OscQueryRange,
+    };
+    pub use crate::server::OscQueryServer;
+}
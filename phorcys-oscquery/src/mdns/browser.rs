@@ -0,0 +1,192 @@
+//! mDNS browser discovering services.
+
+use crate::mdns::{
+    mdns_group_address,
+    message::{Message, Question, RecordData, RecordType, ResourceRecord},
+    name_equals, MdnsResult,
+};
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use async_std::{future::timeout, net::UdpSocket};
+
+/// Describes a discovered service instance.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiscoveredService {
+    /// Full service instance name.
+    pub instance_name: String,
+
+    /// Host name of the service.
+    pub host_name: String,
+
+    /// Service port.
+    pub port: u16,
+
+    /// Resolved socket addresses of the service.
+    pub addresses: Vec<SocketAddr>,
+
+    /// TXT record strings.
+    pub txt: Vec<String>,
+}
+
+/// Discovers service instances of a service type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MdnsBrowser {
+    service_type: String,
+    timeout: Duration,
+    destination: SocketAddr,
+}
+
+impl MdnsBrowser {
+    /// Creates a browser for `service_type`.
+    pub fn new(service_type: &str) -> MdnsBrowser {
+        MdnsBrowser {
+            service_type: service_type.into(),
+            timeout: Duration::from_secs(1),
+            destination: mdns_group_address(),
+        }
+    }
+
+    /// Sets how long to wait for responses.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets where to send the query.
+    /// Defaults to the mDNS multicast group.
+    pub fn destination(mut self, destination: SocketAddr) -> Self {
+        self.destination = destination;
+        self
+    }
+
+    /// Sends a query and collects services responded until timeout.
+    /// The query is sent from an ephemeral port, so responders reply by unicast.
+    pub async fn browse(&self) -> MdnsResult<Vec<DiscoveredService>> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        let query = Message::query(vec![Question {
+            name: self.service_type.clone(),
+            record_type: RecordType::Ptr,
+            unicast_response: true,
+        }]);
+        socket.send_to(&query.encode()?, self.destination).await?;
+
+        let mut records = vec![];
+        let mut buffer = vec![0; 9000];
+        let deadline = Instant::now() + self.timeout;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let (length, _) = match timeout(remaining, socket.recv_from(&mut buffer)).await {
+                Ok(received) => received?,
+                Err(_) => break,
+            };
+            if let Ok(response) = Message::decode(&buffer[..length]) {
+                if response.is_response {
+                    records.extend(response.records().cloned());
+                }
+            }
+        }
+
+        Ok(self.resolve(&records))
+    }
+
+    /// Resolves PTR, SRV, TXT and address records into services.
+    fn resolve(&self, records: &[ResourceRecord]) -> Vec<DiscoveredService> {
+        let mut services: Vec<DiscoveredService> = vec![];
+        let instances = records.iter().filter_map(|r| match &r.data {
+            RecordData::Ptr(instance) if name_equals(&r.name, &self.service_type) => Some(instance),
+            _ => None,
+        });
+
+        for instance in instances {
+            if services
+                .iter()
+                .any(|s| name_equals(&s.instance_name, instance))
+            {
+                continue;
+            }
+            let (host_name, port) = match records.iter().find_map(|r| match &r.data {
+                RecordData::Srv { port, target, .. } if name_equals(&r.name, instance) => {
+                    Some((target.clone(), *port))
+                }
+                _ => None,
+            }) {
+                Some(srv) => srv,
+                None => continue,
+            };
+            let txt = records
+                .iter()
+                .find_map(|r| match &r.data {
+                    RecordData::Txt(txt) if name_equals(&r.name, instance) => Some(txt.clone()),
+                    _ => None,
+                })
+                .unwrap_or_default();
+
+            let mut addresses = vec![];
+            for record in records.iter().filter(|r| name_equals(&r.name, &host_name)) {
+                let address = match record.data {
+                    RecordData::A(a) => SocketAddr::new(IpAddr::V4(a), port),
+                    RecordData::Aaaa(a) => SocketAddr::new(IpAddr::V6(a), port),
+                    _ => continue,
+                };
+                if !addresses.contains(&address) {
+                    addresses.push(address);
+                }
+            }
+
+            services.push(DiscoveredService {
+                instance_name: instance.clone(),
+                host_name,
+                port,
+                addresses,
+                txt,
+            });
+        }
+        services
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MdnsBrowser;
+    use crate::mdns::{MdnsResponder, ServiceInfo, OSCQUERY_SERVICE, OSC_SERVICE};
+
+    use std::{
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        time::Duration,
+    };
+
+    use async_std::{net::UdpSocket, task::spawn};
+
+    /// Ensures the browser discovers services from a responder over loopback.
+    #[async_std::test]
+    async fn test_browse_loopback() {
+        let addresses = vec![IpAddr::V4(Ipv4Addr::LOCALHOST)];
+        let responder = MdnsResponder::new(vec![
+            ServiceInfo::new("phorcys", OSC_SERVICE, 9001, addresses.clone()),
+            ServiceInfo::new("phorcys", OSCQUERY_SERVICE, 8080, addresses),
+        ]);
+        let socket = UdpSocket::bind("127.0.0.1:0").await.expect("Should bind");
+        let address = socket.local_addr().expect("Should have address");
+        let sink = UdpSocket::bind("127.0.0.1:0").await.expect("Should bind");
+        let group = sink.local_addr().expect("Should have address");
+        spawn(async move { responder.run(&socket, group).await });
+
+        let services = MdnsBrowser::new(OSCQUERY_SERVICE)
+            .timeout(Duration::from_millis(500))
+            .destination(address)
+            .browse()
+            .await
+            .expect("Should success");
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].instance_name, "phorcys._oscjson._tcp.local");
+        assert_eq!(services[0].host_name, "phorcys.local");
+        assert_eq!(
+            services[0].addresses,
+            vec![SocketAddr::from((Ipv4Addr::LOCALHOST, 8080))]
+        );
+        assert_eq!(services[0].txt, vec!["txtvers=1".to_string()]);
+    }
+}
@@ -0,0 +1,504 @@
+//! DNS message wire format used in mDNS.
+
+use crate::mdns::{MdnsError, MdnsResult};
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Flag bit of response message.
+const FLAG_RESPONSE: u16 = 0x8000;
+
+/// Flag bit of authoritative answer.
+const FLAG_AUTHORITATIVE: u16 = 0x0400;
+
+/// Top bit of class field.
+/// Means unicast-response in questions and cache-flush in resource records.
+const CLASS_TOP_BIT: u16 = 0x8000;
+
+/// Class `IN`.
+const CLASS_IN: u16 = 1;
+
+/// Maximum depth of name compression pointers to follow.
+const MAX_POINTER_DEPTH: usize = 16;
+
+/// DNS resource record type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RecordType {
+    /// IPv4 address.
+    A,
+
+    /// Domain name pointer.
+    Ptr,
+
+    /// Text strings.
+    Txt,
+
+    /// IPv6 address.
+    Aaaa,
+
+    /// Service locator.
+    Srv,
+
+    /// Any records (question only).
+    Any,
+
+    /// Other types.
+    Other(u16),
+}
+
+impl From<u16> for RecordType {
+    fn from(value: u16) -> RecordType {
+        match value {
+            1 => RecordType::A,
+            12 => RecordType::Ptr,
+            16 => RecordType::Txt,
+            28 => RecordType::Aaaa,
+            33 => RecordType::Srv,
+            255 => RecordType::Any,
+            otherwise => RecordType::Other(otherwise),
+        }
+    }
+}
+
+impl From<RecordType> for u16 {
+    fn from(record_type: RecordType) -> u16 {
+        match record_type {
+            RecordType::A => 1,
+            RecordType::Ptr => 12,
+            RecordType::Txt => 16,
+            RecordType::Aaaa => 28,
+            RecordType::Srv => 33,
+            RecordType::Any => 255,
+            RecordType::Other(value) => value,
+        }
+    }
+}
+
+/// Represents a question entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Question {
+    /// Queried name, without trailing dot.
+    pub name: String,
+
+    /// Queried record type.
+    pub record_type: RecordType,
+
+    /// Whether unicast response is requested (`QU` bit).
+    pub unicast_response: bool,
+}
+
+/// Represents a resource record.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResourceRecord {
+    /// Owner name, without trailing dot.
+    pub name: String,
+
+    /// Time to live in seconds.
+    pub ttl: u32,
+
+    /// Whether caches should flush other records of this name (cache-flush bit).
+    pub cache_flush: bool,
+
+    /// Record data.
+    pub data: RecordData,
+}
+
+/// Data of a resource record.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RecordData {
+    /// IPv4 address.
+    A(Ipv4Addr),
+
+    /// IPv6 address.
+    Aaaa(Ipv6Addr),
+
+    /// Domain name pointer.
+    Ptr(String),
+
+    /// Service locator.
+    Srv {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: String,
+    },
+
+    /// Text strings.
+    Txt(Vec<String>),
+
+    /// Uninterpreted data of other types.
+    Other(u16, Vec<u8>),
+}
+
+impl RecordData {
+    /// Returns the record type.
+    pub fn record_type(&self) -> RecordType {
+        match self {
+            RecordData::A(_) => RecordType::A,
+            RecordData::Aaaa(_) => RecordType::Aaaa,
+            RecordData::Ptr(_) => RecordType::Ptr,
+            RecordData::Srv { .. } => RecordType::Srv,
+            RecordData::Txt(_) => RecordType::Txt,
+            RecordData::Other(t, _) => RecordType::Other(*t),
+        }
+    }
+}
+
+/// Represents a DNS message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Message {
+    /// Transaction ID. Always 0 in multicast messages.
+    pub id: u16,
+
+    /// Whether this message is a response.
+    pub is_response: bool,
+
+    /// Questions.
+    pub questions: Vec<Question>,
+
+    /// Answer records.
+    pub answers: Vec<ResourceRecord>,
+
+    /// Authority records.
+    pub authorities: Vec<ResourceRecord>,
+
+    /// Additional records.
+    pub additionals: Vec<ResourceRecord>,
+}
+
+impl Message {
+    /// Creates a query message.
+    pub fn query(questions: Vec<Question>) -> Message {
+        Message {
+            questions,
+            ..Default::default()
+        }
+    }
+
+    /// Iterates all resource records.
+    pub fn records(&self) -> impl Iterator<Item = &ResourceRecord> {
+        self.answers
+            .iter()
+            .chain(self.authorities.iter())
+            .chain(self.additionals.iter())
+    }
+
+    /// Serializes this message.
+    /// Names are not compressed.
+    pub fn encode(&self) -> MdnsResult<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(512);
+        let flags = if self.is_response {
+            FLAG_RESPONSE | FLAG_AUTHORITATIVE
+        } else {
+            0
+        };
+        for value in [
+            self.id,
+            flags,
+            self.questions.len() as u16,
+            self.answers.len() as u16,
+            self.authorities.len() as u16,
+            self.additionals.len() as u16,
+        ] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+
+        for question in &self.questions {
+            encode_name(&mut bytes, &question.name)?;
+            let class = CLASS_IN
+                | if question.unicast_response {
+                    CLASS_TOP_BIT
+                } else {
+                    0
+                };
+            bytes.extend_from_slice(&u16::from(question.record_type).to_be_bytes());
+            bytes.extend_from_slice(&class.to_be_bytes());
+        }
+        for record in self.records() {
+            encode_record(&mut bytes, record)?;
+        }
+
+        Ok(bytes)
+    }
+
+    /// Deserializes a message.
+    pub fn decode(bytes: &[u8]) -> MdnsResult<Message> {
+        if bytes.len() < 12 {
+            return Err(MdnsError::InvalidMessage);
+        }
+        let word = |i: usize| u16::from_be_bytes([bytes[i], bytes[i + 1]]);
+        let id = word(0);
+        let flags = word(2);
+        let counts = [word(4), word(6), word(8), word(10)];
+
+        let mut offset = 12;
+        let mut questions = vec![];
+        for _ in 0..counts[0] {
+            let name = decode_name(bytes, &mut offset)?;
+            let fields = bytes
+                .get(offset..(offset + 4))
+                .ok_or(MdnsError::InvalidMessage)?;
+            let record_type = u16::from_be_bytes([fields[0], fields[1]]).into();
+            let class = u16::from_be_bytes([fields[2], fields[3]]);
+            offset += 4;
+            questions.push(Question {
+                name,
+                record_type,
+                unicast_response: class & CLASS_TOP_BIT != 0,
+            });
+        }
+
+        let mut sections = [vec![], vec![], vec![]];
+        for (section, count) in sections.iter_mut().zip(&counts[1..]) {
+            for _ in 0..*count {
+                section.push(decode_record(bytes, &mut offset)?);
+            }
+        }
+        let [answers, authorities, additionals] = sections;
+
+        Ok(Message {
+            id,
+            is_response: flags & FLAG_RESPONSE != 0,
+            questions,
+            answers,
+            authorities,
+            additionals,
+        })
+    }
+}
+
+/// Writes a name as labels.
+fn encode_name(bytes: &mut Vec<u8>, name: &str) -> MdnsResult<()> {
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(MdnsError::InvalidMessage);
+        }
+        bytes.push(label.len() as u8);
+        bytes.extend_from_slice(label.as_bytes());
+    }
+    bytes.push(0);
+    Ok(())
+}
+
+/// Writes a resource record.
+fn encode_record(bytes: &mut Vec<u8>, record: &ResourceRecord) -> MdnsResult<()> {
+    encode_name(bytes, &record.name)?;
+    let class = CLASS_IN | if record.cache_flush { CLASS_TOP_BIT } else { 0 };
+    bytes.extend_from_slice(&u16::from(record.data.record_type()).to_be_bytes());
+    bytes.extend_from_slice(&class.to_be_bytes());
+    bytes.extend_from_slice(&record.ttl.to_be_bytes());
+
+    let length_offset = bytes.len();
+    bytes.extend_from_slice(&[0, 0]);
+    match &record.data {
+        RecordData::A(address) => bytes.extend_from_slice(&address.octets()),
+        RecordData::Aaaa(address) => bytes.extend_from_slice(&address.octets()),
+        RecordData::Ptr(name) => encode_name(bytes, name)?,
+        RecordData::Srv {
+            priority,
+            weight,
+            port,
+            target,
+        } => {
+            bytes.extend_from_slice(&priority.to_be_bytes());
+            bytes.extend_from_slice(&weight.to_be_bytes());
+            bytes.extend_from_slice(&port.to_be_bytes());
+            encode_name(bytes, target)?;
+        }
+        RecordData::Txt(strings) if strings.is_empty() => bytes.push(0),
+        RecordData::Txt(strings) => {
+            for string in strings {
+                if string.len() > 255 {
+                    return Err(MdnsError::InvalidMessage);
+                }
+                bytes.push(string.len() as u8);
+                bytes.extend_from_slice(string.as_bytes());
+            }
+        }
+        RecordData::Other(_, data) => bytes.extend_from_slice(data),
+    }
+
+    let data_length = bytes.len() - length_offset - 2;
+    if data_length > u16::MAX as usize {
+        return Err(MdnsError::InvalidMessage);
+    }
+    bytes[length_offset..(length_offset + 2)].copy_from_slice(&(data_length as u16).to_be_bytes());
+    Ok(())
+}
+
+/// Reads a possibly compressed name at `offset`, and advances it.
+fn decode_name(bytes: &[u8], offset: &mut usize) -> MdnsResult<String> {
+    let mut labels: Vec<String> = vec![];
+    let mut position = *offset;
+    let mut jumped = false;
+    let mut depth = 0;
+
+    loop {
+        let length = *bytes.get(position).ok_or(MdnsError::InvalidMessage)? as usize;
+        match length {
+            0 => {
+                if !jumped {
+                    *offset = position + 1;
+                }
+                break;
+            }
+            l if l & 0xC0 == 0xC0 => {
+                let low = *bytes.get(position + 1).ok_or(MdnsError::InvalidMessage)? as usize;
+                if !jumped {
+                    *offset = position + 2;
+                }
+                depth += 1;
+                if depth > MAX_POINTER_DEPTH {
+                    return Err(MdnsError::InvalidMessage);
+                }
+                position = ((l & 0x3F) << 8) | low;
+                jumped = true;
+            }
+            l if l <= 63 => {
+                let label = bytes
+                    .get((position + 1)..(position + 1 + l))
+                    .ok_or(MdnsError::InvalidMessage)?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                position += 1 + l;
+            }
+            _ => return Err(MdnsError::InvalidMessage),
+        }
+    }
+
+    Ok(labels.join("."))
+}
+
+/// Reads a resource record at `offset`, and advances it.
+fn decode_record(bytes: &[u8], offset: &mut usize) -> MdnsResult<ResourceRecord> {
+    let name = decode_name(bytes, offset)?;
+    let fields = bytes
+        .get(*offset..(*offset + 10))
+        .ok_or(MdnsError::InvalidMessage)?;
+    let record_type = u16::from_be_bytes([fields[0], fields[1]]);
+    let class = u16::from_be_bytes([fields[2], fields[3]]);
+    let ttl = u32::from_be_bytes(fields[4..8].try_into().expect("Wrong length"));
+    let data_length = u16::from_be_bytes([fields[8], fields[9]]) as usize;
+    let data_offset = *offset + 10;
+    let data = bytes
+        .get(data_offset..(data_offset + data_length))
+        .ok_or(MdnsError::InvalidMessage)?;
+
+    let data = match RecordType::from(record_type) {
+        RecordType::A if data.len() == 4 => RecordData::A(Ipv4Addr::from(
+            <[u8; 4]>::try_from(data).expect("Wrong length"),
+        )),
+        RecordType::Aaaa if data.len() == 16 => RecordData::Aaaa(Ipv6Addr::from(
+            <[u8; 16]>::try_from(data).expect("Wrong length"),
+        )),
+        RecordType::Ptr => RecordData::Ptr(decode_name(bytes, &mut data_offset.clone())?),
+        RecordType::Srv if data.len() >= 6 => RecordData::Srv {
+            priority: u16::from_be_bytes([data[0], data[1]]),
+            weight: u16::from_be_bytes([data[2], data[3]]),
+            port: u16::from_be_bytes([data[4], data[5]]),
+            target: decode_name(bytes, &mut (data_offset + 6))?,
+        },
+        RecordType::Txt => {
+            let mut strings = vec![];
+            let mut rest = data;
+            while let Some((&length, tail)) = rest.split_first() {
+                let string = tail
+                    .get(..(length as usize))
+                    .ok_or(MdnsError::InvalidMessage)?;
+                if !string.is_empty() {
+                    strings.push(String::from_utf8_lossy(string).into_owned());
+                }
+                rest = &tail[(length as usize)..];
+            }
+            RecordData::Txt(strings)
+        }
+        _ => RecordData::Other(record_type, data.into()),
+    };
+
+    *offset = data_offset + data_length;
+    Ok(ResourceRecord {
+        name,
+        ttl,
+        cache_flush: class & CLASS_TOP_BIT != 0,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Message, Question, RecordData, RecordType, ResourceRecord};
+
+    use std::net::Ipv4Addr;
+
+    /// Ensures messages are encoded and decoded back.
+    #[test]
+    fn test_message_roundtrip() {
+        let message = Message {
+            id: 0,
+            is_response: true,
+            questions: vec![Question {
+                name: "_osc._udp.local".into(),
+                record_type: RecordType::Ptr,
+                unicast_response: true,
+            }],
+            answers: vec![ResourceRecord {
+                name: "_osc._udp.local".into(),
+                ttl: 4500,
+                cache_flush: false,
+                data: RecordData::Ptr("phorcys._osc._udp.local".into()),
+            }],
+            authorities: vec![],
+            additionals: vec![
+                ResourceRecord {
+                    name: "phorcys._osc._udp.local".into(),
+                    ttl: 120,
+                    cache_flush: true,
+                    data: RecordData::Srv {
+                        priority: 0,
+                        weight: 0,
+                        port: 9001,
+                        target: "phorcys.local".into(),
+                    },
+                },
+                ResourceRecord {
+                    name: "phorcys._osc._udp.local".into(),
+                    ttl: 4500,
+                    cache_flush: true,
+                    data: RecordData::Txt(vec!["txtvers=1".into()]),
+                },
+                ResourceRecord {
+                    name: "phorcys.local".into(),
+                    ttl: 120,
+                    cache_flush: true,
+                    data: RecordData::A(Ipv4Addr::LOCALHOST),
+                },
+            ],
+        };
+
+        let bytes = message.encode().expect("Should encode");
+        assert_eq!(Message::decode(&bytes).expect("Should decode"), message);
+    }
+
+    /// Ensures compressed names are decoded.
+    #[test]
+    fn test_message_compression() {
+        let bytes = [
+            0x00, 0x00, 0x84, 0x00, // ID, flags
+            0x00, 0x00, 0x00, 0x01, // Questions, answers
+            0x00, 0x00, 0x00, 0x00, // Authorities, additionals
+            0x04, b'_', b'o', b's', b'c', // _osc
+            0x04, b'_', b'u', b'd', b'p', // _udp
+            0x05, b'l', b'o', b'c', b'a', b'l', 0x00, // local
+            0x00, 0x0C, 0x00, 0x01, // PTR, IN
+            0x00, 0x00, 0x11, 0x94, // TTL
+            0x00, 0x04, // Data length
+            0x01, b'x', 0xC0, 0x0C, // x + pointer to _osc._udp.local
+        ];
+
+        let message = Message::decode(&bytes).expect("Should decode");
+        assert_eq!(message.answers[0].name, "_osc._udp.local");
+        assert_eq!(
+            message.answers[0].data,
+            RecordData::Ptr("x._osc._udp.local".into())
+        );
+        assert!(Message::decode(&bytes[..40]).is_err());
+    }
+}
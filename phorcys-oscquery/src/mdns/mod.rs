@@ -0,0 +1,78 @@
+//! mDNS / DNS-SD advertisement and discovery of OSC and OSCQuery services.
+
+pub mod browser;
+pub mod message;
+pub mod responder;
+
+pub use crate::mdns::{
+    browser::{DiscoveredService, MdnsBrowser},
+    responder::{MdnsResponder, ServiceInfo},
+};
+
+use std::{
+    io::Error as IoError,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket as StdUdpSocket},
+    result::Result as StdResult,
+};
+
+use async_std::net::UdpSocket;
+use socket2::{Domain, Protocol, Socket, Type};
+use thiserror::Error as ThisError;
+
+/// mDNS IPv4 multicast group.
+pub const MDNS_GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+
+/// mDNS port.
+pub const MDNS_PORT: u16 = 5353;
+
+/// DNS-SD service type of OSC servers.
+pub const OSC_SERVICE: &str = "_osc._udp.local";
+
+/// DNS-SD service type of OSCQuery servers.
+pub const OSCQUERY_SERVICE: &str = "_oscjson._tcp.local";
+
+/// DNS-SD service type enumeration name.
+pub const SERVICES_ENUMERATION: &str = "_services._dns-sd._udp.local";
+
+/// Represents an error in mDNS.
+#[non_exhaustive]
+#[derive(Debug, ThisError)]
+pub enum MdnsError {
+    /// Socket error.
+    #[error("I/O error: {0}")]
+    Io(#[from] IoError),
+
+    /// Malformed DNS message.
+    #[error("Invalid DNS message")]
+    InvalidMessage,
+}
+
+/// Result type shorthand with `MdnsError`.
+pub type MdnsResult<T> = StdResult<T, MdnsError>;
+
+/// Returns mDNS multicast group address.
+pub fn mdns_group_address() -> SocketAddr {
+    SocketAddr::V4(SocketAddrV4::new(MDNS_GROUP, MDNS_PORT))
+}
+
+/// Creates a socket bound to mDNS port and joined to the multicast group on `interface`.
+/// The port is shared with other mDNS responders on the host.
+pub fn multicast_socket(interface: Ipv4Addr) -> MdnsResult<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    socket.bind(&SocketAddr::from((Ipv4Addr::UNSPECIFIED, MDNS_PORT)).into())?;
+    socket.join_multicast_v4(&MDNS_GROUP, &interface)?;
+    socket.set_multicast_loop_v4(true)?;
+    socket.set_multicast_ttl_v4(255)?;
+
+    let socket: StdUdpSocket = socket.into();
+    Ok(socket.into())
+}
+
+/// Returns whether two DNS names are equal, ignoring ASCII case and trailing dot.
+fn name_equals(a: &str, b: &str) -> bool {
+    a.trim_end_matches('.')
+        .eq_ignore_ascii_case(b.trim_end_matches('.'))
+}
@@ -0,0 +1,444 @@
+//! mDNS responder advertising services.
+
+use crate::mdns::{
+    message::{Message, Question, RecordData, RecordType, ResourceRecord},
+    name_equals, MdnsResult, MDNS_PORT, SERVICES_ENUMERATION,
+};
+
+use std::{
+    future::Future,
+    net::{IpAddr, SocketAddr},
+};
+
+use async_std::net::UdpSocket;
+use futures::{
+    future::{select, Either},
+    pin_mut,
+};
+use log::warn;
+
+/// TTL of records containing host names.
+const HOST_TTL: u32 = 120;
+
+/// TTL of other records.
+const OTHER_TTL: u32 = 4500;
+
+/// Maximum TTL in responses to legacy unicast queries (RFC 6762 section 6.7).
+const LEGACY_UNICAST_TTL: u32 = 10;
+
+/// Describes an advertised service instance.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServiceInfo {
+    /// Instance name. It must not contain dots.
+    pub instance: String,
+
+    /// Service type such as `_osc._udp.local`.
+    pub service_type: String,
+
+    /// Host name such as `phorcys.local`.
+    pub host_name: String,
+
+    /// Service port.
+    pub port: u16,
+
+    /// Addresses of the host.
+    pub addresses: Vec<IpAddr>,
+
+    /// TXT record strings.
+    pub txt: Vec<String>,
+}
+
+impl ServiceInfo {
+    /// Creates a service description.
+    /// Host name is derived from the instance name.
+    pub fn new(
+        instance: &str,
+        service_type: &str,
+        port: u16,
+        addresses: Vec<IpAddr>,
+    ) -> ServiceInfo {
+        ServiceInfo {
+            instance: instance.into(),
+            service_type: service_type.into(),
+            host_name: format!("{}.local", instance),
+            port,
+            addresses,
+            txt: vec!["txtvers=1".into()],
+        }
+    }
+
+    /// Returns the full service instance name.
+    pub fn full_name(&self) -> String {
+        format!("{}.{}", self.instance, self.service_type)
+    }
+
+    /// Creates PTR record of this service.
+    fn ptr_record(&self) -> ResourceRecord {
+        ResourceRecord {
+            name: self.service_type.clone(),
+            ttl: OTHER_TTL,
+            cache_flush: false,
+            data: RecordData::Ptr(self.full_name()),
+        }
+    }
+
+    /// Creates SRV and TXT records of this service.
+    fn service_records(&self) -> Vec<ResourceRecord> {
+        vec![
+            ResourceRecord {
+                name: self.full_name(),
+                ttl: HOST_TTL,
+                cache_flush: true,
+                data: RecordData::Srv {
+                    priority: 0,
+                    weight: 0,
+                    port: self.port,
+                    target: self.host_name.clone(),
+                },
+            },
+            ResourceRecord {
+                name: self.full_name(),
+                ttl: OTHER_TTL,
+                cache_flush: true,
+                data: RecordData::Txt(self.txt.clone()),
+            },
+        ]
+    }
+
+    /// Creates address records of the host.
+    fn address_records(&self, record_type: RecordType) -> Vec<ResourceRecord> {
+        self.addresses
+            .iter()
+            .filter_map(|address| {
+                let data = match (address, record_type) {
+                    (IpAddr::V4(a), RecordType::A | RecordType::Any) => RecordData::A(*a),
+                    (IpAddr::V6(a), RecordType::Aaaa | RecordType::Any) => RecordData::Aaaa(*a),
+                    _ => return None,
+                };
+                Some(ResourceRecord {
+                    name: self.host_name.clone(),
+                    ttl: HOST_TTL,
+                    cache_flush: true,
+                    data,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Answers mDNS queries for registered services.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MdnsResponder {
+    services: Vec<ServiceInfo>,
+}
+
+impl MdnsResponder {
+    /// Creates a responder for `services`.
+    pub fn new(services: Vec<ServiceInfo>) -> MdnsResponder {
+        MdnsResponder { services }
+    }
+
+    /// Returns registered services.
+    pub fn services(&self) -> &[ServiceInfo] {
+        &self.services
+    }
+
+    /// Creates an unsolicited response announcing all services.
+    pub fn announcement(&self) -> Message {
+        let mut message = Message {
+            is_response: true,
+            ..Default::default()
+        };
+        for service in &self.services {
+            message.answers.push(service.ptr_record());
+            message.answers.extend(service.service_records());
+            message
+                .answers
+                .extend(service.address_records(RecordType::Any));
+        }
+        message
+    }
+
+    /// Creates an unsolicited response withdrawing all services.
+    /// It is the same as the announcement, but all TTLs are zero.
+    pub fn goodbye(&self) -> Message {
+        let mut message = self.announcement();
+        for record in &mut message.answers {
+            record.ttl = 0;
+        }
+        message
+    }
+
+    /// Creates a response for `query`.
+    /// Returns `None` if no question is for registered services.
+    pub fn answer(&self, query: &Message) -> Option<Message> {
+        if query.is_response {
+            return None;
+        }
+
+        let mut answers = vec![];
+        let mut additionals = vec![];
+        for question in &query.questions {
+            for service in &self.services {
+                let any = question.record_type == RecordType::Any;
+                if name_equals(&question.name, SERVICES_ENUMERATION)
+                    && (any || question.record_type == RecordType::Ptr)
+                {
+                    answers.push(ResourceRecord {
+                        name: SERVICES_ENUMERATION.into(),
+                        ttl: OTHER_TTL,
+                        cache_flush: false,
+                        data: RecordData::Ptr(service.service_type.clone()),
+                    });
+                } else if name_equals(&question.name, &service.service_type)
+                    && (any || question.record_type == RecordType::Ptr)
+                {
+                    answers.push(service.ptr_record());
+                    additionals.extend(service.service_records());
+                    additionals.extend(service.address_records(RecordType::Any));
+                } else if name_equals(&question.name, &service.full_name()) {
+                    let records = service
+                        .service_records()
+                        .into_iter()
+                        .filter(|r| any || r.data.record_type() == question.record_type);
+                    answers.extend(records);
+                    additionals.extend(service.address_records(RecordType::Any));
+                } else if name_equals(&question.name, &service.host_name) {
+                    answers.extend(service.address_records(question.record_type));
+                }
+            }
+        }
+
+        if answers.is_empty() {
+            return None;
+        }
+        let answers = unique_records(answers);
+        additionals.retain(|r| !answers.contains(r));
+        let additionals = unique_records(additionals);
+
+        Some(Message {
+            id: 0,
+            is_response: true,
+            questions: vec![],
+            answers,
+            authorities: vec![],
+            additionals,
+        })
+    }
+
+    /// Announces services to `group` and answers queries arriving at `socket`.
+    /// It returns only when the initial announcement fails;
+    /// failures to receive or respond afterwards are logged and skipped.
+    ///
+    /// Queries from ports other than 5353 are answered by unicast as legacy queries.
+    /// Queries with unicast-response bit are also answered by unicast.
+    /// Other queries are answered to `group`.
+    ///
+    /// Goodbye packets are not sent when this future is dropped; use `run_until` for that.
+    pub async fn run(&self, socket: &UdpSocket, group: SocketAddr) -> MdnsResult<()> {
+        socket
+            .send_to(&self.announcement().encode()?, group)
+            .await?;
+
+        let mut buffer = vec![0; 9000];
+        loop {
+            let (length, peer) = match socket.recv_from(&mut buffer).await {
+                Ok(r) => r,
+                Err(e) => {
+                    warn!("Failed to receive mDNS query: {}", e);
+                    continue;
+                }
+            };
+            let query = match Message::decode(&buffer[..length]) {
+                Ok(q) => q,
+                Err(_) => continue,
+            };
+            let mut response = match self.answer(&query) {
+                Some(r) => r,
+                None => continue,
+            };
+
+            let destination = if peer.port() != MDNS_PORT {
+                response = legacy_response(response, &query);
+                peer
+            } else if query
+                .questions
+                .iter()
+                .all(|q: &Question| q.unicast_response)
+            {
+                peer
+            } else {
+                group
+            };
+            if let Err(e) = socket.send_to(&response.encode()?, destination).await {
+                warn!("Failed to send mDNS response to {}: {}", destination, e);
+            }
+        }
+    }
+
+    /// Runs as `run` until `shutdown` completes, then sends goodbye packets to `group`.
+    pub async fn run_until(
+        &self,
+        socket: &UdpSocket,
+        group: SocketAddr,
+        shutdown: impl Future<Output = ()>,
+    ) -> MdnsResult<()> {
+        let running = self.run(socket, group);
+        pin_mut!(running, shutdown);
+        if let Either::Left((result, _)) = select(running, shutdown).await {
+            return result;
+        }
+
+        socket.send_to(&self.goodbye().encode()?, group).await?;
+        Ok(())
+    }
+}
+
+/// Converts a response into the form for legacy unicast `query` (RFC 6762 section 6.7).
+/// The query ID and questions are echoed, cache-flush bits are cleared and TTLs are capped.
+fn legacy_response(mut response: Message, query: &Message) -> Message {
+    response.id = query.id;
+    response.questions = query.questions.clone();
+    for record in response
+        .answers
+        .iter_mut()
+        .chain(response.additionals.iter_mut())
+    {
+        record.cache_flush = false;
+        record.ttl = record.ttl.min(LEGACY_UNICAST_TTL);
+    }
+    response
+}
+
+/// Removes duplicated records, keeping the first occurrences in order.
+fn unique_records(records: Vec<ResourceRecord>) -> Vec<ResourceRecord> {
+    let mut unique: Vec<ResourceRecord> = Vec::with_capacity(records.len());
+    for record in records {
+        if !unique.contains(&record) {
+            unique.push(record);
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod test {
+    use super::{legacy_response, MdnsResponder, ServiceInfo};
+    use crate::mdns::{
+        message::{Message, Question, RecordData, RecordType},
+        OSCQUERY_SERVICE, OSC_SERVICE,
+    };
+
+    use std::net::{IpAddr, Ipv4Addr};
+
+    fn responder() -> MdnsResponder {
+        let addresses = vec![IpAddr::V4(Ipv4Addr::LOCALHOST)];
+        MdnsResponder::new(vec![
+            ServiceInfo::new("phorcys", OSC_SERVICE, 9001, addresses.clone()),
+            ServiceInfo::new("phorcys", OSCQUERY_SERVICE, 8080, addresses),
+        ])
+    }
+
+    /// Ensures PTR queries are answered with SRV, TXT and A records.
+    #[test]
+    fn test_responder_ptr() {
+        let query = Message::query(vec![Question {
+            name: "_OSC._udp.local".into(),
+            record_type: RecordType::Ptr,
+            unicast_response: false,
+        }]);
+
+        let response = responder().answer(&query).expect("Should answer");
+        assert!(response.is_response);
+        assert_eq!(response.answers.len(), 1);
+        assert_eq!(
+            response.answers[0].data,
+            RecordData::Ptr("phorcys._osc._udp.local".into())
+        );
+        assert!(response.additionals.iter().any(|r| r.data
+            == RecordData::Srv {
+                priority: 0,
+                weight: 0,
+                port: 9001,
+                target: "phorcys.local".into(),
+            }));
+        assert!(response
+            .additionals
+            .iter()
+            .any(|r| r.data == RecordData::A(Ipv4Addr::LOCALHOST)));
+    }
+
+    /// Ensures unrelated queries are ignored.
+    #[test]
+    fn test_responder_unrelated() {
+        let query = Message::query(vec![Question {
+            name: "_http._tcp.local".into(),
+            record_type: RecordType::Ptr,
+            unicast_response: false,
+        }]);
+        assert_eq!(responder().answer(&query), None);
+    }
+
+    /// Ensures records are not duplicated even if questions overlap.
+    #[test]
+    fn test_responder_unique() {
+        let question = |name: &str, record_type| Question {
+            name: name.into(),
+            record_type,
+            unicast_response: false,
+        };
+        let query = Message::query(vec![
+            question("phorcys.local", RecordType::A),
+            question("_osc._udp.local", RecordType::Ptr),
+            question("phorcys.local", RecordType::A),
+        ]);
+
+        let response = responder().answer(&query).expect("Should answer");
+        for records in [&response.answers, &response.additionals] {
+            for (i, record) in records.iter().enumerate() {
+                assert!(!records[..i].contains(record));
+            }
+        }
+        assert_eq!(
+            response
+                .answers
+                .iter()
+                .filter(|r| r.data == RecordData::A(Ipv4Addr::LOCALHOST))
+                .count(),
+            1
+        );
+    }
+
+    /// Ensures legacy unicast responses have capped TTLs and no cache-flush bits.
+    #[test]
+    fn test_responder_legacy() {
+        let mut query = Message::query(vec![Question {
+            name: "_osc._udp.local".into(),
+            record_type: RecordType::Ptr,
+            unicast_response: false,
+        }]);
+        query.id = 0x1234;
+
+        let response = responder().answer(&query).expect("Should answer");
+        assert!(response.additionals.iter().any(|r| r.cache_flush));
+        let response = legacy_response(response, &query);
+        assert_eq!(response.id, 0x1234);
+        assert_eq!(response.questions, query.questions);
+        for record in response.answers.iter().chain(&response.additionals) {
+            assert!(!record.cache_flush);
+            assert!(record.ttl <= 10);
+        }
+    }
+
+    /// Ensures goodbye packets withdraw all announced records.
+    #[test]
+    fn test_responder_goodbye() {
+        let responder = responder();
+        let goodbye = responder.goodbye();
+        assert_eq!(
+            goodbye.answers.len(),
+            responder.announcement().answers.len()
+        );
+        assert!(goodbye.answers.iter().all(|r| r.ttl == 0));
+    }
+}
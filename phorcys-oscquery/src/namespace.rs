@@ -0,0 +1,570 @@
+//! OSCQuery namespace tree and host information.
+
+use std::{collections::BTreeMap, net::SocketAddr};
+
+use phorcys_config::vrchat::config::{Configuration, ParameterDataType};
+use phorcys_osc::{
+    address::{Address, AddressStr},
+    data::Value,
+    signature::TypeTags,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Number as JsonNumber, Value as JsonValue};
+
+/// Access mode of an OSC method. Serialized as an integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(into = "u8", try_from = "u8")]
+pub enum Access {
+    /// No value can be retrieved or set.
+    None,
+
+    /// Value can be retrieved.
+    Read,
+
+    /// Value can be set.
+    Write,
+
+    /// Value can be both retrieved and set.
+    ReadWrite,
+}
+
+impl From<Access> for u8 {
+    fn from(access: Access) -> u8 {
+        match access {
+            Access::None => 0,
+            Access::Read => 1,
+            Access::Write => 2,
+            Access::ReadWrite => 3,
+        }
+    }
+}
+
+impl TryFrom<u8> for Access {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Access, String> {
+        match value {
+            0 => Ok(Access::None),
+            1 => Ok(Access::Read),
+            2 => Ok(Access::Write),
+            3 => Ok(Access::ReadWrite),
+            _ => Err(format!("Invalid access mode: {}", value)),
+        }
+    }
+}
+
+/// Range of an argument.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct Range {
+    /// Minimum value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<JsonValue>,
+
+    /// Maximum value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<JsonValue>,
+
+    /// Enumeration of accepted values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vals: Option<Vec<JsonValue>>,
+}
+
+impl Range {
+    /// Creates a range with minimum and maximum.
+    pub fn between(min: impl Into<JsonValue>, max: impl Into<JsonValue>) -> Range {
+        Range {
+            min: Some(min.into()),
+            max: Some(max.into()),
+            vals: None,
+        }
+    }
+}
+
+/// Represents a node in OSCQuery namespace.
+/// A node with `type_tags` is an OSC method, otherwise it is a container.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct Node {
+    /// Full path of this node. Root node has `/`.
+    pub full_path: String,
+
+    /// Child nodes keyed by their last path part.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub contents: BTreeMap<String, Node>,
+
+    /// Type tag string without leading comma. Originally named `TYPE`.
+    #[serde(rename = "TYPE", default, skip_serializing_if = "Option::is_none")]
+    pub type_tags: Option<String>,
+
+    /// Access mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access: Option<Access>,
+
+    /// Ranges for each argument.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<Vec<Range>>,
+
+    /// Human-readable description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Current values for each argument.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Vec<JsonValue>>,
+}
+
+impl Node {
+    /// Creates an empty root container.
+    pub fn root() -> Node {
+        Node::container("/")
+    }
+
+    /// Creates an empty container.
+    pub fn container(full_path: &str) -> Node {
+        Node {
+            full_path: full_path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates an OSC method node.
+    /// Full path will be set on insertion.
+    pub fn method(type_tags: &str, access: Access) -> Node {
+        Node {
+            type_tags: Some(type_tags.into()),
+            access: Some(access),
+            ..Default::default()
+        }
+    }
+
+    /// Sets ranges.
+    pub fn with_range(mut self, range: Vec<Range>) -> Node {
+        self.range = Some(range);
+        self
+    }
+
+    /// Sets description.
+    pub fn with_description(mut self, description: &str) -> Node {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets current values.
+    pub fn with_value(mut self, values: &[Value]) -> Node {
+        self.value = Some(values.iter().map(value_to_json).collect());
+        self
+    }
+
+    /// Whether this node is an OSC method.
+    pub fn is_method(&self) -> bool {
+        self.type_tags.is_some()
+    }
+
+    /// Inserts `node` at `address`, creating intermediate containers.
+    /// Existing children of the replaced node are kept.
+    pub fn insert(&mut self, address: &AddressStr, node: Node) {
+        self.insert_path(address.as_ref(), node);
+    }
+
+    /// Finds the node at `address`.
+    pub fn find(&self, address: &AddressStr) -> Option<&Node> {
+        self.get(address.as_ref())
+    }
+
+    /// Finds the node at `address` mutably.
+    pub fn find_mut(&mut self, address: &AddressStr) -> Option<&mut Node> {
+        self.get_mut(address.as_ref())
+    }
+
+    /// Finds the node at `path` relative to this node.
+    /// `path` may contain characters which are not allowed in `Address`, such as spaces.
+    pub fn get(&self, path: &str) -> Option<&Node> {
+        let mut node = self;
+        for part in path_parts(path)? {
+            node = node.contents.get(part)?;
+        }
+        Some(node)
+    }
+
+    /// Finds the node at `path` relative to this node mutably.
+    pub fn get_mut(&mut self, path: &str) -> Option<&mut Node> {
+        let mut node = self;
+        for part in path_parts(path)? {
+            node = node.contents.get_mut(part)?;
+        }
+        Some(node)
+    }
+
+    /// Sets current values of the method at `address`.
+    /// Returns `false` if the method does not exist.
+    pub fn set_value(&mut self, address: &AddressStr, values: &[Value]) -> bool {
+        match self.find_mut(address) {
+            Some(node) if node.is_method() => {
+                node.value = Some(values.iter().map(value_to_json).collect());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Builds a namespace containing parameters of VRChat avatar configuration.
+    /// Parameters with input are writable, and those with output are readable.
+    /// Parameters of unknown data types are skipped.
+    pub fn from_vrc_configuration(configuration: &Configuration) -> Node {
+        let mut root = Node::root();
+        for parameter in &configuration.parameters {
+            let access = match &parameter.input {
+                Some(input) if input.address == parameter.output.address => Access::ReadWrite,
+                _ => Access::Read,
+            };
+            let (type_tags, range) = match parameter.output.parameter_type {
+                ParameterDataType::Bool => ("T", None),
+                ParameterDataType::Int => ("i", Some(Range::between(0, 255))),
+                ParameterDataType::Float => ("f", Some(Range::between(-1.0, 1.0))),
+                ParameterDataType::Other(_) => continue,
+            };
+
+            let mut node = Node::method(type_tags, access).with_description(&parameter.name);
+            node.range = range.map(|r| vec![r]);
+            root.insert_path(&parameter.output.address, node.clone());
+            if let Some(input) = &parameter.input {
+                if input.address != parameter.output.address {
+                    node.access = Some(Access::Write);
+                    root.insert_path(&input.address, node);
+                }
+            }
+        }
+        root
+    }
+
+    /// Collects all OSC method nodes in this tree, in path order.
+    pub fn methods(&self) -> Vec<&Node> {
+        let mut methods = vec![];
+        if self.is_method() {
+            methods.push(self);
+        }
+        for child in self.contents.values() {
+            methods.extend(child.methods());
+        }
+        methods
+    }
+
+    /// Returns the address and OSC type tags of this method.
+    /// Returns `None` if this is not a method, the path is not a valid `Address` or type tags are invalid.
+    pub fn signature(&self) -> Option<(Address, TypeTags)> {
+        let type_tags = self.type_tags.as_ref()?;
+        let address = Address::new(&self.full_path).ok()?;
+        let type_tags = TypeTags::new(&format!(",{}", type_tags)).ok()?;
+        Some((address, type_tags))
+    }
+
+    /// Collects signatures of all methods in this tree.
+    /// Methods whose path is not a valid `Address` are skipped.
+    /// The result can be collected into `SignatureRegistry`.
+    pub fn signatures(&self) -> Vec<(Address, TypeTags)> {
+        self.methods()
+            .into_iter()
+            .filter_map(Node::signature)
+            .collect()
+    }
+
+    /// Converts current JSON values into OSC values following type tags.
+    /// Returns `None` if no value is set or values do not match the type tags.
+    /// Integers out of `i32` range for `i` tag are regarded as mismatches instead of being truncated.
+    pub fn values(&self) -> Option<Vec<Value>> {
+        let type_tags = self.type_tags.as_ref()?;
+        let values = self.value.as_ref()?;
+
+        let mut tags = type_tags.bytes();
+        let converted = json_to_values(&mut tags, values.iter())?;
+        match tags.next() {
+            None => Some(converted),
+            Some(_) => None,
+        }
+    }
+
+    /// Inserts `node` at `path`.
+    /// Invalid paths are ignored.
+    fn insert_path(&mut self, path: &str, mut node: Node) {
+        let parts = match path_parts(path) {
+            Some(p) if !p.is_empty() => p,
+            _ => return,
+        };
+
+        let mut parent = self;
+        let mut full_path = String::new();
+        for part in &parts[..(parts.len() - 1)] {
+            full_path.push('/');
+            full_path.push_str(part);
+            parent = parent
+                .contents
+                .entry(part.to_string())
+                .or_insert_with(|| Node::container(&full_path));
+        }
+
+        let last_part = parts[parts.len() - 1];
+        node.full_path = format!("{}/{}", full_path, last_part);
+        if let Some(existing) = parent.contents.remove(last_part) {
+            node.contents.extend(existing.contents);
+        }
+        parent.contents.insert(last_part.into(), node);
+    }
+}
+
+impl From<&Configuration> for Node {
+    fn from(configuration: &Configuration) -> Node {
+        Node::from_vrc_configuration(configuration)
+    }
+}
+
+/// Host information returned for `HOST_INFO` query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct HostInfo {
+    /// Server name.
+    pub name: String,
+
+    /// Supported attributes and extensions.
+    #[serde(default)]
+    pub extensions: BTreeMap<String, bool>,
+
+    /// IP address of OSC server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub osc_ip: Option<String>,
+
+    /// Port of OSC server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub osc_port: Option<u16>,
+
+    /// Transport protocol of OSC server, `UDP` or `TCP`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub osc_transport: Option<String>,
+}
+
+impl HostInfo {
+    /// Creates host information for OSC server over UDP at `osc_address`.
+    pub fn new(name: &str, osc_address: SocketAddr) -> HostInfo {
+        let extensions = [
+            ("ACCESS", true),
+            ("VALUE", true),
+            ("RANGE", true),
+            ("DESCRIPTION", true),
+            ("TAGS", false),
+            ("EXTENDED_TYPE", false),
+            ("UNIT", false),
+            ("CRITICAL", false),
+            ("CLIPMODE", false),
+            ("LISTEN", false),
+            ("PATH_CHANGED", false),
+        ];
+
+        HostInfo {
+            name: name.into(),
+            extensions: extensions
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+            osc_ip: Some(osc_address.ip().to_string()),
+            osc_port: Some(osc_address.port()),
+            osc_transport: Some("UDP".into()),
+        }
+    }
+}
+
+/// Converts OSC value into JSON value.
+/// Values without JSON representation become `null`.
+pub fn value_to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::Boolean(b) => JsonValue::Bool(*b),
+        Value::Int32(i) => JsonValue::Number((*i).into()),
+        Value::Int64(i) => JsonValue::Number((*i).into()),
+        Value::Float32(f) => JsonNumber::from_f64(*f as f64)
+            .map(JsonValue::Number)
+            .unwrap_or(JsonValue::Null),
+        Value::Float64(f) => JsonNumber::from_f64(*f)
+            .map(JsonValue::Number)
+            .unwrap_or(JsonValue::Null),
+        Value::Character(c) => JsonValue::String(c.to_string()),
+        Value::String(s) | Value::Alternative(s) => JsonValue::String(s.clone()),
+        Value::Array(values) => JsonValue::Array(values.iter().map(value_to_json).collect()),
+        _ => JsonValue::Null,
+    }
+}
+
+/// Converts JSON values consuming type tags.
+fn json_to_values<'a>(
+    tags: &mut impl Iterator<Item = u8>,
+    values: impl Iterator<Item = &'a JsonValue>,
+) -> Option<Vec<Value>> {
+    let mut converted = vec![];
+    for value in values {
+        let tag = tags.next()?;
+        let value = match (tag, value) {
+            (b'i', v) => Value::Int32(i32::try_from(v.as_i64()?).ok()?),
+            (b'h', v) => Value::Int64(v.as_i64()?),
+            (b'f', v) => Value::Float32(v.as_f64()? as f32),
+            (b'd', v) => Value::Float64(v.as_f64()?),
+            (b'T' | b'F', JsonValue::Bool(b)) => Value::Boolean(*b),
+            (b'T', JsonValue::Null) => Value::Boolean(true),
+            (b'F', JsonValue::Null) => Value::Boolean(false),
+            (b'N', _) => Value::Nil,
+            (b'I', _) => Value::Infinitum,
+            (b's', JsonValue::String(s)) => Value::String(s.clone()),
+            (b'S', JsonValue::String(s)) => Value::Alternative(s.clone()),
+            (b'c', JsonValue::String(s)) => Value::Character(s.chars().next()?),
+            (b'[', JsonValue::Array(inner)) => {
+                let inner = json_to_values(tags, inner.iter())?;
+                if tags.next()? != b']' {
+                    return None;
+                }
+                Value::Array(inner)
+            }
+            _ => return None,
+        };
+        converted.push(value);
+    }
+    Some(converted)
+}
+
+/// Splits path into parts. Root path `/` has no part.
+fn path_parts(path: &str) -> Option<Vec<&str>> {
+    match path {
+        "/" => Some(vec![]),
+        p if p.starts_with('/') => {
+            let parts: Vec<_> = p[1..].split('/').collect();
+            if parts.iter().any(|p| p.is_empty()) {
+                None
+            } else {
+                Some(parts)
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Access, Node, Range};
+
+    use phorcys_config::vrchat::config::{
+        Configuration, Parameter, ParameterAddress, ParameterDataType,
+    };
+    use phorcys_osc::{address::Address, data::Value};
+    use serde_json::{json, to_value};
+
+    /// Ensures nodes are inserted with intermediate containers and serialized in OSCQuery form.
+    #[test]
+    fn test_namespace_insert() {
+        let mut root = Node::root();
+        let address = Address::new("/foo/bar").expect("Should valid");
+        root.insert(
+            &address,
+            Node::method("f", Access::ReadWrite)
+                .with_range(vec![Range::between(0.0, 1.0)])
+                .with_value(&[Value::Float32(0.5)]),
+        );
+
+        assert_eq!(
+            to_value(&root).expect("Should serialize"),
+            json!({
+                "FULL_PATH": "/",
+                "CONTENTS": {
+                    "foo": {
+                        "FULL_PATH": "/foo",
+                        "CONTENTS": {
+                            "bar": {
+                                "FULL_PATH": "/foo/bar",
+                                "TYPE": "f",
+                                "ACCESS": 3,
+                                "RANGE": [{ "MIN": 0.0, "MAX": 1.0 }],
+                                "VALUE": [0.5],
+                            }
+                        }
+                    }
+                }
+            })
+        );
+
+        assert!(root.set_value(&address, &[Value::Float32(1.0)]));
+        assert_eq!(
+            root.find(&address).and_then(|n| n.value.clone()),
+            Some(vec![json!(1.0)])
+        );
+        assert!(!root.set_value(&Address::new("/foo").expect("Should valid"), &[]));
+    }
+
+    /// Ensures VRChat avatar configuration is converted into namespace.
+    #[test]
+    fn test_namespace_from_configuration() {
+        let configuration = Configuration {
+            id: "avtr_00000000-0000-0000-0000-000000000000".into(),
+            name: "Avatar".into(),
+            parameters: vec![
+                Parameter {
+                    name: "Face Shocked".into(),
+                    input: Some(ParameterAddress {
+                        address: "/avatar/parameters/Face Shocked".into(),
+                        parameter_type: ParameterDataType::Bool,
+                        unknown_fields: Default::default(),
+                    }),
+                    output: ParameterAddress {
+                        address: "/avatar/parameters/Face Shocked".into(),
+                        parameter_type: ParameterDataType::Bool,
+                        unknown_fields: Default::default(),
+                    },
+                    unknown_fields: Default::default(),
+                },
+                Parameter {
+                    name: "VRMode".into(),
+                    input: None,
+                    output: ParameterAddress {
+                        address: "/avatar/parameters/VRMode".into(),
+                        parameter_type: ParameterDataType::Int,
+                        unknown_fields: Default::default(),
+                    },
+                    unknown_fields: Default::default(),
+                },
+            ],
+            unknown_fields: Default::default(),
+        };
+
+        let root = Node::from(&configuration);
+        let shocked = root
+            .get("/avatar/parameters/Face Shocked")
+            .expect("Should exist");
+        assert_eq!(shocked.type_tags.as_deref(), Some("T"));
+        assert_eq!(shocked.access, Some(Access::ReadWrite));
+
+        let vr_mode = root.get("/avatar/parameters/VRMode").expect("Should exist");
+        assert_eq!(vr_mode.full_path, "/avatar/parameters/VRMode");
+        assert_eq!(vr_mode.access, Some(Access::Read));
+        assert_eq!(vr_mode.range, Some(vec![Range::between(0, 255)]));
+        assert!(!root.get("/avatar").expect("Should exist").is_method());
+    }
+
+    /// Ensures JSON values are converted following type tags.
+    #[test]
+    fn test_node_values() {
+        let mut node = Node::method("iT[fs]", Access::Read);
+        node.value =
+            Some(serde_json::from_str(r#"[1, true, [0.5, "a"]]"#).expect("Should be JSON"));
+        assert_eq!(
+            node.values(),
+            Some(vec![
+                Value::Int32(1),
+                Value::Boolean(true),
+                Value::Array(vec![Value::Float32(0.5), Value::String("a".into())]),
+            ])
+        );
+
+        // Integers out of `i32` range are not truncated.
+        node.type_tags = Some("ii".into());
+        node.value = Some(serde_json::from_str(r#"[-1, 4294967296]"#).expect("Should be JSON"));
+        assert_eq!(node.values(), None);
+
+        node.type_tags = Some("is".into());
+        node.value = Some(serde_json::from_str(r#"[1, 2]"#).expect("Should be JSON"));
+        assert_eq!(node.values(), None);
+    }
+}
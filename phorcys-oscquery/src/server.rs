@@ -0,0 +1,221 @@
+//! OSCQuery HTTP server.
+
+use crate::namespace::{HostInfo, Node};
+
+use async_std::{
+    io::Result as IoResult,
+    net::TcpListener,
+    sync::{Arc, RwLock},
+};
+use percent_encoding::percent_decode_str;
+use phorcys_osc::{address::AddressStr, data::Value};
+use serde_json::{to_value as to_json_value, Map as JsonMap};
+use tide::{Body, Request, Response, StatusCode};
+
+/// Attributes which can be queried by `?ATTRIBUTE`.
+const QUERYABLE_ATTRIBUTES: &[&str] = &[
+    "FULL_PATH",
+    "CONTENTS",
+    "TYPE",
+    "ACCESS",
+    "RANGE",
+    "DESCRIPTION",
+    "VALUE",
+];
+
+/// Serves OSCQuery namespace and host information over HTTP.
+/// Cloned servers share the same namespace.
+#[derive(Debug, Clone)]
+pub struct OscQueryServer {
+    state: Arc<ServerState>,
+}
+
+#[derive(Debug)]
+struct ServerState {
+    host_info: HostInfo,
+    root: RwLock<Node>,
+}
+
+impl OscQueryServer {
+    /// Creates a server with host information and namespace root.
+    pub fn new(host_info: HostInfo, root: Node) -> OscQueryServer {
+        OscQueryServer {
+            state: Arc::new(ServerState {
+                host_info,
+                root: RwLock::new(root),
+            }),
+        }
+    }
+
+    /// Returns host information.
+    pub fn host_info(&self) -> &HostInfo {
+        &self.state.host_info
+    }
+
+    /// Returns a snapshot of the namespace.
+    pub async fn namespace(&self) -> Node {
+        self.state.root.read().await.clone()
+    }
+
+    /// Inserts a node into the namespace.
+    pub async fn insert(&self, address: &AddressStr, node: Node) {
+        self.state.root.write().await.insert(address, node);
+    }
+
+    /// Sets current values of the method at `address`.
+    /// Returns `false` if the method does not exist.
+    pub async fn set_value(&self, address: &AddressStr, values: &[Value]) -> bool {
+        self.state.root.write().await.set_value(address, values)
+    }
+
+    /// Serves HTTP requests on `listener` until an error occurs.
+    pub async fn listen(self, listener: TcpListener) -> IoResult<()> {
+        let mut app = tide::with_state(self.state);
+        app.at("/").get(handle_request);
+        app.at("*").get(handle_request);
+        app.listen(listener).await
+    }
+}
+
+/// Handles a namespace or attribute query.
+async fn handle_request(request: Request<Arc<ServerState>>) -> tide::Result {
+    let state = request.state();
+    let path = percent_decode_str(request.url().path()).decode_utf8_lossy();
+    let query = request.url().query();
+
+    if query == Some("HOST_INFO") {
+        return json_response(to_json_value(&state.host_info)?);
+    }
+
+    let root = state.root.read().await;
+    let node = match root.get(&path) {
+        Some(n) => n,
+        None => return Ok(Response::new(StatusCode::NotFound)),
+    };
+
+    match query {
+        None => json_response(to_json_value(node)?),
+        Some(attribute) if QUERYABLE_ATTRIBUTES.contains(&attribute) => {
+            let mut node_json = match to_json_value(node)? {
+                serde_json::Value::Object(o) => o,
+                _ => unreachable!("Node should be an object"),
+            };
+            match node_json.remove(attribute) {
+                Some(value) => {
+                    let mut response = JsonMap::new();
+                    response.insert(attribute.into(), value);
+                    json_response(response.into())
+                }
+                None => Ok(Response::new(StatusCode::NoContent)),
+            }
+        }
+        Some(_) => Ok(Response::new(StatusCode::BadRequest)),
+    }
+}
+
+/// Creates a JSON response.
+fn json_response(value: serde_json::Value) -> tide::Result {
+    Ok(Response::builder(StatusCode::Ok)
+        .body(Body::from_json(&value)?)
+        .build())
+}
+
+#[cfg(test)]
+mod test {
+    use super::OscQueryServer;
+    use crate::namespace::{Access, HostInfo, Node};
+
+    use async_std::{
+        io::{prelude::*, ReadExt},
+        net::{SocketAddr, TcpListener, TcpStream},
+        task::spawn,
+    };
+    use phorcys_osc::{address::Address, data::Value};
+    use serde_json::{from_str as json_from_str, json, Value as JsonValue};
+
+    async fn start_server() -> (OscQueryServer, SocketAddr) {
+        let mut root = Node::root();
+        root.insert(
+            &Address::new("/avatar/parameters/Foo").expect("Should valid"),
+            Node::method("f", Access::ReadWrite).with_description("Foo"),
+        );
+        let server = OscQueryServer::new(
+            HostInfo::new(
+                "phorcys-test",
+                "127.0.0.1:9001".parse().expect("Should valid"),
+            ),
+            root,
+        );
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("Should bind");
+        let address = listener.local_addr().expect("Should have address");
+        spawn(server.clone().listen(listener));
+        (server, address)
+    }
+
+    async fn get(address: SocketAddr, target: &str) -> (u16, Option<JsonValue>) {
+        let mut stream = TcpStream::connect(address).await.expect("Should connect");
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            target, address
+        );
+        stream
+            .write_all(request.as_bytes())
+            .await
+            .expect("Should send");
+
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .await
+            .expect("Should receive");
+        let status = response[9..12].parse().expect("Should have status");
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, b)| b)
+            .filter(|b| !b.is_empty())
+            .map(|b| json_from_str(b).expect("Should be JSON"));
+        (status, body)
+    }
+
+    /// Ensures namespace, attributes and host information are served.
+    #[async_std::test]
+    async fn test_server_queries() {
+        let (server, address) = start_server().await;
+
+        let (status, body) = get(address, "/avatar/parameters/Foo").await;
+        assert_eq!(status, 200);
+        let body = body.expect("Should have body");
+        assert_eq!(body["FULL_PATH"], json!("/avatar/parameters/Foo"));
+        assert_eq!(body["TYPE"], json!("f"));
+
+        let (status, _) = get(address, "/avatar/parameters/Foo?VALUE").await;
+        assert_eq!(status, 204);
+
+        server
+            .set_value(
+                &Address::new("/avatar/parameters/Foo").expect("Should valid"),
+                &[Value::Float32(0.5)],
+            )
+            .await;
+        let (_, body) = get(address, "/avatar/parameters/Foo?VALUE").await;
+        assert_eq!(body, Some(json!({ "VALUE": [0.5] })));
+
+        let (_, body) = get(address, "/avatar/parameters/Foo?TYPE").await;
+        assert_eq!(body, Some(json!({ "TYPE": "f" })));
+
+        let (_, body) = get(address, "/?HOST_INFO").await;
+        let body = body.expect("Should have body");
+        assert_eq!(body["NAME"], json!("phorcys-test"));
+        assert_eq!(body["OSC_PORT"], json!(9001));
+        assert_eq!(body["OSC_TRANSPORT"], json!("UDP"));
+
+        let (_, body) = get(address, "/").await;
+        assert!(body.expect("Should have body")["CONTENTS"]["avatar"].is_object());
+
+        let (status, _) = get(address, "/avatar/parameters/Bar").await;
+        assert_eq!(status, 404);
+        let (status, _) = get(address, "/avatar?UNKNOWN").await;
+        assert_eq!(status, 400);
+    }
+}
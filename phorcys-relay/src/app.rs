@@ -0,0 +1,156 @@
+use std::net::SocketAddr;
+
+use clap::Parser;
+use phorcys_osc::transform::TransformDescription;
+use serde::Deserialize;
+
+/// Relays OSC packets between VRChat and multiple tools.
+#[derive(Debug, Clone, Parser)]
+#[clap(author, version)]
+pub struct Arguments {
+    /// Filename of relay configuration TOML.
+    pub configuration: String,
+}
+
+/// Relay configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Configuration {
+    /// Socket address for receiving packets from VRChat.
+    #[serde(default = "default_listen")]
+    pub listen: SocketAddr,
+
+    /// Socket address of VRChat to which packets from tools are relayed.
+    #[serde(default = "default_vrchat")]
+    pub vrchat: SocketAddr,
+
+    /// Socket address for receiving packets from tools and relaying them to VRChat.
+    pub return_listen: Option<SocketAddr>,
+
+    /// Transforms applied to packets received at `return_listen`.
+    #[serde(default)]
+    pub return_transform: Vec<TransformDescription>,
+
+    /// WebSocket bridge.
+    pub websocket: Option<WebSocketConfiguration>,
+
+    /// MQTT bridge.
+    pub mqtt: Option<MqttConfiguration>,
+
+    /// Relay targets.
+    #[serde(rename = "target", default)]
+    pub targets: Vec<TargetConfiguration>,
+}
+
+/// Relay target configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TargetConfiguration {
+    /// Name for logging.
+    pub name: String,
+
+    /// Socket address of the tool.
+    pub address: SocketAddr,
+
+    /// Transforms applied to packets from VRChat to this target.
+    #[serde(default)]
+    pub transform: Vec<TransformDescription>,
+
+    /// Whether packets which the tool sends back to the relaying socket are relayed to VRChat.
+    #[serde(default)]
+    pub bidirectional: bool,
+
+    /// Transforms applied to packets from this target to VRChat.
+    #[serde(default)]
+    pub return_transform: Vec<TransformDescription>,
+}
+
+/// WebSocket bridge configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct WebSocketConfiguration {
+    /// Socket address for accepting WebSocket clients.
+    pub listen: SocketAddr,
+}
+
+/// MQTT bridge configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MqttConfiguration {
+    /// Host name of the broker.
+    pub host: String,
+
+    /// Port of the broker.
+    #[serde(default = "default_mqtt_port")]
+    pub port: u16,
+
+    /// Client ID.
+    #[serde(default = "default_mqtt_client_id")]
+    pub client_id: String,
+
+    /// User name for authentication.
+    pub username: Option<String>,
+
+    /// Password for authentication.
+    pub password: Option<String>,
+
+    /// Topic prefix; `/avatar/parameters/Foo` is mapped to `<prefix>/avatar/parameters/Foo`.
+    #[serde(default = "default_mqtt_prefix")]
+    pub prefix: String,
+
+    /// Payload encoding.
+    #[serde(default)]
+    pub encoding: PayloadEncoding,
+
+    /// QoS level of publishing and subscription (0, 1 or 2).
+    #[serde(default)]
+    pub qos: u8,
+
+    /// Whether published messages are retained.
+    #[serde(default)]
+    pub retain: bool,
+
+    /// Duration in seconds in which the same payload crossing back is regarded as a loop.
+    #[serde(default = "default_mqtt_loop_window")]
+    pub loop_window: f64,
+
+    /// Transforms applied to packets from VRChat to MQTT.
+    #[serde(default)]
+    pub transform: Vec<TransformDescription>,
+
+    /// Transforms applied to packets from MQTT to VRChat.
+    #[serde(default)]
+    pub return_transform: Vec<TransformDescription>,
+}
+
+/// Payload encoding of MQTT messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadEncoding {
+    /// JSON value of the argument, or JSON array of arguments if not single.
+    #[default]
+    Json,
+
+    /// Text of the argument, or space-separated texts of arguments if not single.
+    Plain,
+}
+
+fn default_listen() -> SocketAddr {
+    ([127, 0, 0, 1], 9001).into()
+}
+
+fn default_vrchat() -> SocketAddr {
+    ([127, 0, 0, 1], 9000).into()
+}
+
+fn default_mqtt_port() -> u16 {
+    1883
+}
+
+fn default_mqtt_client_id() -> String {
+    "phorcys-relay".into()
+}
+
+fn default_mqtt_prefix() -> String {
+    "vrchat".into()
+}
+
+fn default_mqtt_loop_window() -> f64 {
+    0.5
+}
@@ -0,0 +1,136 @@
+//! JSON representation of OSC values.
+//!
+//! Arguments of types JSON can express naturally are written as is:
+//! `N` as `null`, `T`/`F` as booleans, `i` and `f` as numbers, `s` as strings and arrays as arrays.
+//! Other types are written as objects with type tag, like `{ "type": "h", "value": 1 }`.
+
+use anyhow::{bail, Context, Result};
+use phorcys_osc::prelude::*;
+use serde_json::{json, Map, Value as JsonValue};
+
+/// Converts OSC value into JSON value.
+pub fn value_to_json(value: &OscValue) -> JsonValue {
+    match value {
+        OscValue::Nil => JsonValue::Null,
+        OscValue::Boolean(b) => json!(b),
+        OscValue::Int32(x) => json!(x),
+        OscValue::Float32(x) => json!(x),
+        OscValue::String(s) => json!(s),
+        OscValue::Array(values) => values.iter().map(value_to_json).collect(),
+        OscValue::Infinitum => json!({ "type": "I" }),
+        OscValue::Character(c) => json!({ "type": "c", "value": c }),
+        OscValue::Int64(x) => json!({ "type": "h", "value": x }),
+        OscValue::Float64(x) => json!({ "type": "d", "value": x }),
+        OscValue::Color(c) => json!({ "type": "r", "value": c }),
+        OscValue::MidiMessage(m) => json!({ "type": "m", "value": m }),
+        OscValue::TimeTag(t) => json!({ "type": "t", "value": t.0 }),
+        OscValue::Alternative(s) => json!({ "type": "S", "value": s }),
+        OscValue::Blob(b) => json!({ "type": "b", "value": b }),
+    }
+}
+
+/// Converts JSON value into OSC value.
+/// Integer numbers become `i` (or `h` if out of range), and others become `f`.
+pub fn value_from_json(value: &JsonValue) -> Result<OscValue> {
+    let value = match value {
+        JsonValue::Null => OscValue::Nil,
+        JsonValue::Bool(b) => OscValue::Boolean(*b),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(x) => match i32::try_from(x) {
+                Ok(x) => OscValue::Int32(x),
+                Err(_) => OscValue::Int64(x),
+            },
+            None => OscValue::Float32(n.as_f64().context("Invalid number")? as f32),
+        },
+        JsonValue::String(s) => OscValue::String(s.clone()),
+        JsonValue::Array(values) => OscValue::Array(
+            values
+                .iter()
+                .map(value_from_json)
+                .collect::<Result<Vec<_>>>()?,
+        ),
+        JsonValue::Object(object) => typed_value_from_json(object)?,
+    };
+    Ok(value)
+}
+
+/// Converts `{ "type": ..., "value": ... }` into OSC value.
+fn typed_value_from_json(object: &Map<String, JsonValue>) -> Result<OscValue> {
+    let type_tag = object
+        .get("type")
+        .and_then(|t| t.as_str())
+        .context("Typed value must have \"type\"")?;
+    let value = object.get("value").cloned().unwrap_or(JsonValue::Null);
+
+    let value = match type_tag {
+        "N" => OscValue::Nil,
+        "I" => OscValue::Infinitum,
+        "T" => OscValue::Boolean(true),
+        "F" => OscValue::Boolean(false),
+        "i" => OscValue::Int32(serde_json::from_value(value)?),
+        "h" => OscValue::Int64(serde_json::from_value(value)?),
+        "f" => OscValue::Float32(serde_json::from_value(value)?),
+        "d" => OscValue::Float64(serde_json::from_value(value)?),
+        "c" => OscValue::Character(serde_json::from_value(value)?),
+        "r" => OscValue::Color(serde_json::from_value(value)?),
+        "m" => OscValue::MidiMessage(serde_json::from_value(value)?),
+        "t" => OscValue::TimeTag(phorcys_osc::data::TimeTag(serde_json::from_value(value)?)),
+        "s" => OscValue::String(serde_json::from_value(value)?),
+        "S" => OscValue::Alternative(serde_json::from_value(value)?),
+        "b" => OscValue::Blob(serde_json::from_value(value)?),
+        otherwise => bail!("Unknown type tag: {}", otherwise),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{value_from_json, value_to_json};
+
+    use phorcys_osc::{data::TimeTag, prelude::*};
+    use serde_json::json;
+
+    /// Ensures OSC values survive conversion through JSON.
+    #[test]
+    fn test_value_roundtrip() {
+        let values = vec![
+            OscValue::Nil,
+            OscValue::Infinitum,
+            OscValue::Boolean(true),
+            OscValue::Character('a'),
+            OscValue::Int32(-1),
+            OscValue::Int64(1 << 40),
+            OscValue::Float32(0.5),
+            OscValue::Float64(0.25),
+            OscValue::Color([1, 2, 3, 4]),
+            OscValue::MidiMessage([0x90, 60, 127, 0]),
+            OscValue::TimeTag(TimeTag(1)),
+            OscValue::String("foo".into()),
+            OscValue::Alternative("bar".into()),
+            OscValue::Blob(vec![0, 255]),
+            OscValue::Array(vec![OscValue::Int32(1), OscValue::Boolean(false)]),
+        ];
+        for value in values {
+            let json = value_to_json(&value);
+            assert_eq!(value_from_json(&json).expect("Should success"), value);
+        }
+    }
+
+    /// Ensures plain JSON numbers are mapped by their forms.
+    #[test]
+    fn test_value_from_json_numbers() {
+        assert_eq!(
+            value_from_json(&json!(1)).expect("Should success"),
+            OscValue::Int32(1)
+        );
+        assert_eq!(
+            value_from_json(&json!(1.5)).expect("Should success"),
+            OscValue::Float32(1.5)
+        );
+        assert_eq!(
+            value_from_json(&json!(1u64 << 40)).expect("Should success"),
+            OscValue::Int64(1 << 40)
+        );
+        assert!(value_from_json(&json!({ "type": "x" })).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+mod app;
+mod json;
+mod mqtt;
+mod relay;
+mod websocket;
+
+use crate::{
+    app::{Arguments, Configuration},
+    relay::run_relay,
+};
+
+use anyhow::Result;
+use async_std::fs::read_to_string;
+use clap::Parser;
+use flexi_logger::Logger;
+
+#[async_std::main]
+async fn main() -> Result<()> {
+    Logger::try_with_env()?.start()?;
+    let args = Arguments::parse();
+
+    let configuration_source = read_to_string(&args.configuration).await?;
+    let configuration: Configuration = toml::from_str(&configuration_source)?;
+    run_relay(configuration).await
+}
@@ -0,0 +1,154 @@
+//! Mapping between OSC messages and MQTT topics and payloads.
+
+use crate::{
+    app::PayloadEncoding,
+    json::{value_from_json, value_to_json},
+};
+
+use anyhow::Result;
+use phorcys_osc::prelude::*;
+use serde_json::Value as JsonValue;
+
+/// Maps OSC address into MQTT topic.
+pub fn address_to_topic(prefix: &str, address: &OscAddressStr) -> String {
+    format!("{}{}", prefix, address)
+}
+
+/// Maps MQTT topic into OSC address.
+/// Returns `None` if the topic is not under the prefix or not a valid address.
+pub fn topic_to_address(prefix: &str, topic: &str) -> Option<OscAddressBuf> {
+    let address = topic.strip_prefix(prefix)?;
+    OscAddressBuf::new(address).ok()
+}
+
+/// Returns MQTT topic filter which covers all topics under the prefix.
+pub fn topic_filter(prefix: &str) -> String {
+    if prefix.is_empty() {
+        "#".into()
+    } else {
+        format!("{}/#", prefix)
+    }
+}
+
+/// Encodes OSC arguments into MQTT payload.
+pub fn encode_payload(encoding: PayloadEncoding, arguments: &[OscValue]) -> Vec<u8> {
+    match (encoding, arguments) {
+        (_, []) => vec![],
+        (PayloadEncoding::Json, [value]) => value_to_json(value).to_string().into_bytes(),
+        (PayloadEncoding::Json, values) => {
+            JsonValue::Array(values.iter().map(value_to_json).collect())
+                .to_string()
+                .into_bytes()
+        }
+        (PayloadEncoding::Plain, values) => values
+            .iter()
+            .map(plain_text)
+            .collect::<Vec<_>>()
+            .join(" ")
+            .into_bytes(),
+    }
+}
+
+/// Decodes MQTT payload into OSC arguments.
+///
+/// JSON array is decoded as multiple arguments.
+/// Plain payload is always decoded as a single argument.
+pub fn decode_payload(encoding: PayloadEncoding, payload: &[u8]) -> Result<Vec<OscValue>> {
+    if payload.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let arguments = match encoding {
+        PayloadEncoding::Json => match serde_json::from_slice(payload)? {
+            JsonValue::Array(values) => values
+                .iter()
+                .map(value_from_json)
+                .collect::<Result<Vec<_>>>()?,
+            value => vec![value_from_json(&value)?],
+        },
+        PayloadEncoding::Plain => vec![plain_value(std::str::from_utf8(payload)?.trim())],
+    };
+    Ok(arguments)
+}
+
+/// Writes a value as plain text.
+/// Floats always have decimal point so that they are decoded as floats again.
+fn plain_text(value: &OscValue) -> String {
+    match value {
+        OscValue::Float32(x) => format!("{:?}", x),
+        OscValue::Float64(x) => format!("{:?}", x),
+        OscValue::String(s) | OscValue::Alternative(s) => s.clone(),
+        OscValue::Character(c) => c.to_string(),
+        otherwise => otherwise.to_string(),
+    }
+}
+
+/// Reads a value from plain text.
+fn plain_value(text: &str) -> OscValue {
+    match text {
+        "true" => OscValue::Boolean(true),
+        "false" => OscValue::Boolean(false),
+        _ => {
+            if let Ok(x) = text.parse() {
+                OscValue::Int32(x)
+            } else if let Ok(x) = text.parse() {
+                OscValue::Float32(x)
+            } else {
+                OscValue::String(text.into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{address_to_topic, decode_payload, encode_payload, topic_filter, topic_to_address};
+    use crate::app::PayloadEncoding;
+
+    use phorcys_osc::prelude::*;
+
+    /// Ensures addresses and topics are mapped each other.
+    #[test]
+    fn test_topic_mapping() {
+        let address = OscAddressBuf::new("/avatar/parameters/Foo").expect("Should valid");
+        let topic = address_to_topic("vrchat", &address);
+        assert_eq!(topic, "vrchat/avatar/parameters/Foo");
+        assert_eq!(topic_to_address("vrchat", &topic), Some(address));
+
+        assert_eq!(topic_to_address("vrchat", "other/avatar"), None);
+        assert_eq!(topic_to_address("vrchat", "vrchatfoo/bar"), None);
+        assert_eq!(topic_to_address("vrchat", "vrchat/foo bar"), None);
+        assert_eq!(topic_filter("vrchat"), "vrchat/#");
+        assert_eq!(topic_filter(""), "#");
+    }
+
+    /// Ensures payloads survive encoding and decoding.
+    #[test]
+    fn test_payload_roundtrip() {
+        let cases = [
+            vec![],
+            vec![OscValue::Float32(1.0)],
+            vec![OscValue::Int32(-3)],
+            vec![OscValue::Boolean(true)],
+            vec![OscValue::String("foo bar".into())],
+        ];
+        for encoding in [PayloadEncoding::Json, PayloadEncoding::Plain] {
+            for arguments in &cases {
+                let payload = encode_payload(encoding, arguments);
+                assert_eq!(
+                    &decode_payload(encoding, &payload).expect("Should success"),
+                    arguments
+                );
+            }
+        }
+
+        let arguments = vec![OscValue::Int32(1), OscValue::Float32(0.5)];
+        let payload = encode_payload(PayloadEncoding::Json, &arguments);
+        assert_eq!(payload, b"[1,0.5]");
+        assert_eq!(
+            decode_payload(PayloadEncoding::Json, &payload).expect("Should success"),
+            arguments
+        );
+        assert_eq!(encode_payload(PayloadEncoding::Plain, &arguments), b"1 0.5");
+    }
+}
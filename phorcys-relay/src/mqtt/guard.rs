@@ -0,0 +1,63 @@
+//! Loop prevention between OSC and MQTT.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Remembers payloads which recently crossed the bridge.
+///
+/// Without this, a message published to MQTT is delivered back to the bridge by the broker,
+/// and a parameter sent to VRChat is echoed back as OSC message.
+/// When a payload comes back on the same topic within the window, it is regarded as the echo and dropped.
+#[derive(Debug)]
+pub struct LoopGuard {
+    window: Duration,
+    recent: HashMap<String, (Vec<u8>, Instant)>,
+}
+
+impl LoopGuard {
+    /// Creates a guard with the window.
+    pub fn new(window: Duration) -> LoopGuard {
+        LoopGuard {
+            window,
+            recent: HashMap::new(),
+        }
+    }
+
+    /// Whether the payload may cross the bridge.
+    /// Passing payloads are recorded, and an echo is forgotten once it is detected.
+    pub fn pass(&mut self, topic: &str, payload: &[u8], now: Instant) -> bool {
+        if let Some((recent_payload, at)) = self.recent.get(topic) {
+            if recent_payload == payload && now.duration_since(*at) < self.window {
+                self.recent.remove(topic);
+                return false;
+            }
+        }
+
+        self.recent.insert(topic.into(), (payload.to_vec(), now));
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LoopGuard;
+
+    use std::time::{Duration, Instant};
+
+    /// Ensures echoes within the window are dropped once.
+    #[test]
+    fn test_loop_guard() {
+        let mut guard = LoopGuard::new(Duration::from_millis(500));
+        let now = Instant::now();
+
+        assert!(guard.pass("vrchat/foo", b"1", now));
+        assert!(!guard.pass("vrchat/foo", b"1", now + Duration::from_millis(100)));
+        assert!(guard.pass("vrchat/foo", b"1", now + Duration::from_millis(200)));
+
+        assert!(guard.pass("vrchat/foo", b"2", now + Duration::from_millis(300)));
+        assert!(guard.pass("vrchat/bar", b"2", now + Duration::from_millis(300)));
+        assert!(guard.pass("vrchat/foo", b"2", now + Duration::from_millis(900)));
+    }
+}
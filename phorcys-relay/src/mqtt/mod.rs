@@ -0,0 +1,209 @@
+//! MQTT bridge.
+//!
+//! OSC addresses are mapped to topics under the prefix, like
+//! `/avatar/parameters/Foo` and `vrchat/avatar/parameters/Foo`.
+
+mod codec;
+mod guard;
+
+use crate::{
+    app::{MqttConfiguration, PayloadEncoding},
+    mqtt::{
+        codec::{address_to_topic, decode_payload, encode_payload, topic_filter, topic_to_address},
+        guard::LoopGuard,
+    },
+    relay::{build_pipeline, flatten_messages},
+};
+
+use std::{
+    sync::Mutex,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Result};
+use async_std::{
+    net::UdpSocket,
+    sync::Arc,
+    task::{block_on, spawn_blocking, JoinHandle},
+};
+use log::{info, trace, warn};
+use phorcys_osc::prelude::*;
+use rumqttc::{Client, Connection, Event, MqttOptions, Packet, Publish, QoS};
+
+/// Capacity of request channel to the MQTT event loop.
+const REQUEST_CAPACITY: usize = 64;
+
+/// Delay before reconnecting to the broker.
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// MQTT bridge shared between workers.
+pub struct MqttBridge {
+    client: Mutex<Client>,
+    prefix: String,
+    encoding: PayloadEncoding,
+    qos: QoS,
+    retain: bool,
+
+    /// Pipeline for packets from VRChat.
+    pipeline: OscPipeline,
+
+    /// Pipeline for packets to VRChat.
+    return_pipeline: OscPipeline,
+
+    guard: Mutex<LoopGuard>,
+}
+
+impl MqttBridge {
+    /// Connects to the broker and starts a worker relaying MQTT messages to VRChat.
+    pub fn start(
+        configuration: MqttConfiguration,
+        vrchat_socket: Arc<UdpSocket>,
+    ) -> Result<(Arc<MqttBridge>, JoinHandle<Result<()>>)> {
+        let qos = match configuration.qos {
+            0 => QoS::AtMostOnce,
+            1 => QoS::AtLeastOnce,
+            2 => QoS::ExactlyOnce,
+            otherwise => bail!("Invalid QoS level: {}", otherwise),
+        };
+        if !configuration.loop_window.is_finite() || configuration.loop_window < 0.0 {
+            bail!("Invalid loop window: {}", configuration.loop_window);
+        }
+
+        let mut options = MqttOptions::new(
+            &configuration.client_id,
+            &configuration.host,
+            configuration.port,
+        );
+        if let Some(username) = &configuration.username {
+            options.set_credentials(
+                username,
+                configuration.password.as_deref().unwrap_or_default(),
+            );
+        }
+        let (client, connection) = Client::new(options, REQUEST_CAPACITY);
+        info!(
+            "Bridging MQTT broker {}:{} with topics {}",
+            configuration.host,
+            configuration.port,
+            topic_filter(&configuration.prefix)
+        );
+
+        let bridge = Arc::new(MqttBridge {
+            client: Mutex::new(client),
+            prefix: configuration.prefix,
+            encoding: configuration.encoding,
+            qos,
+            retain: configuration.retain,
+            pipeline: build_pipeline(configuration.transform)?,
+            return_pipeline: build_pipeline(configuration.return_transform)?,
+            guard: Mutex::new(LoopGuard::new(Duration::from_secs_f64(
+                configuration.loop_window,
+            ))),
+        });
+
+        let worker_bridge = bridge.clone();
+        let worker =
+            spawn_blocking(move || subscribe_worker(worker_bridge, connection, vrchat_socket));
+        Ok((bridge, worker))
+    }
+
+    /// Publishes messages in the packet from VRChat.
+    pub fn publish(&self, packet: OscPacket) {
+        let packet = match self.pipeline.transform(packet) {
+            Some(p) => p,
+            None => return,
+        };
+
+        for message in flatten_messages(packet) {
+            let topic = address_to_topic(&self.prefix, message.address());
+            let payload = encode_payload(self.encoding, message.arguments());
+            if !self.pass_guard(&topic, &payload) {
+                trace!("Suppressed looping message to MQTT: {}", topic);
+                continue;
+            }
+
+            let mut client = self.client.lock().expect("Poisoned");
+            if let Err(e) = client.try_publish(topic, self.qos, self.retain, payload) {
+                warn!("Failed to publish to MQTT: {}", e);
+            }
+        }
+    }
+
+    /// Converts a message from MQTT into OSC packet.
+    fn receive(&self, publish: &Publish) -> Option<OscPacket> {
+        let address = topic_to_address(&self.prefix, &publish.topic)?;
+        if !self.pass_guard(&publish.topic, &publish.payload) {
+            trace!("Suppressed looping message from MQTT: {}", publish.topic);
+            return None;
+        }
+
+        let arguments = match decode_payload(self.encoding, &publish.payload) {
+            Ok(a) => a,
+            Err(e) => {
+                warn!("Invalid payload on {}: {}", publish.topic, e);
+                return None;
+            }
+        };
+        let message = OscMessageBuilder::from_address(address)
+            .set_arguments(arguments)
+            .build();
+        self.return_pipeline.transform(OscPacket::Message(message))
+    }
+
+    fn pass_guard(&self, topic: &str, payload: &[u8]) -> bool {
+        let mut guard = self.guard.lock().expect("Poisoned");
+        guard.pass(topic, payload, Instant::now())
+    }
+}
+
+/// Drives MQTT connection and relays subscribed messages to VRChat.
+fn subscribe_worker(
+    bridge: Arc<MqttBridge>,
+    mut connection: Connection,
+    vrchat_socket: Arc<UdpSocket>,
+) -> Result<()> {
+    let mut subscription_pending = false;
+    for event in connection.iter() {
+        match event {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                // Subscriptions do not survive reconnection with clean session.
+                info!("Connected to MQTT broker");
+                subscription_pending = true;
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                let packet = match bridge.receive(&publish) {
+                    Some(p) => p,
+                    None => continue,
+                };
+                trace!("Relaying to VRChat from MQTT: {:?}", packet);
+                if let Err(e) = block_on(vrchat_socket.send(&packet.serialize())) {
+                    warn!("Failed to relay to VRChat: {}", e);
+                }
+            }
+            Ok(_) => (),
+            Err(e) => {
+                warn!("MQTT connection error: {}", e);
+                sleep(RECONNECT_DELAY);
+            }
+        }
+
+        // Failed subscription is retried after the connection processes pending requests.
+        if subscription_pending {
+            let subscribed = bridge
+                .client
+                .lock()
+                .expect("Poisoned")
+                .try_subscribe(topic_filter(&bridge.prefix), bridge.qos);
+            match subscribed {
+                Ok(()) => subscription_pending = false,
+                Err(e) => {
+                    warn!("Failed to subscribe MQTT topics: {}", e);
+                    sleep(RECONNECT_DELAY);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
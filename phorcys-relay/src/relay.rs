@@ -0,0 +1,370 @@
+use crate::{
+    app::{Configuration, TargetConfiguration},
+    mqtt::MqttBridge,
+    websocket::{websocket_worker, Clients},
+};
+
+use anyhow::Result;
+use async_std::{
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket},
+    sync::Arc,
+    task::spawn,
+};
+use futures::future::try_join_all;
+use log::{info, trace, warn};
+use phorcys_osc::{
+    prelude::*,
+    transform::{PipelineDescription, TransformDescription},
+};
+
+/// Maximum size of receiving UDP datagram.
+const RECEIVE_BUFFER_SIZE: usize = 65536;
+
+/// Relay target.
+#[derive(Debug)]
+struct Target {
+    /// Name for logging.
+    name: String,
+
+    /// Socket connected to the tool.
+    socket: UdpSocket,
+
+    /// Pipeline for packets from VRChat.
+    pipeline: OscPipeline,
+
+    /// Pipeline for packets to VRChat, if bidirectional.
+    return_pipeline: Option<OscPipeline>,
+}
+
+impl Target {
+    async fn new(configuration: TargetConfiguration) -> Result<Target> {
+        let socket = connected_socket(configuration.address).await?;
+        let pipeline = build_pipeline(configuration.transform)?;
+        let return_pipeline = if configuration.bidirectional {
+            Some(build_pipeline(configuration.return_transform)?)
+        } else {
+            None
+        };
+
+        info!(
+            "Target \"{}\": {} ({} transform(s){})",
+            configuration.name,
+            configuration.address,
+            pipeline.len(),
+            if return_pipeline.is_some() {
+                ", bidirectional"
+            } else {
+                ""
+            }
+        );
+        Ok(Target {
+            name: configuration.name,
+            socket,
+            pipeline,
+            return_pipeline,
+        })
+    }
+}
+
+/// Starts relaying with the configuration.
+pub async fn run_relay(configuration: Configuration) -> Result<()> {
+    let listen_socket = UdpSocket::bind(configuration.listen).await?;
+    let vrchat_socket = Arc::new(connected_socket(configuration.vrchat).await?);
+    info!("Receiving packets from VRChat at {}", configuration.listen);
+    info!("Relaying packets to VRChat at {}", configuration.vrchat);
+
+    let mut targets = vec![];
+    for target_configuration in configuration.targets {
+        targets.push(Arc::new(Target::new(target_configuration).await?));
+    }
+
+    let mut workers = vec![];
+    for target in &targets {
+        if target.return_pipeline.is_some() {
+            workers.push(spawn(target_return_worker(
+                target.clone(),
+                vrchat_socket.clone(),
+            )));
+        }
+    }
+    if let Some(return_listen) = configuration.return_listen {
+        let socket = UdpSocket::bind(return_listen).await?;
+        let pipeline = build_pipeline(configuration.return_transform)?;
+        info!("Receiving packets from tools at {}", return_listen);
+        workers.push(spawn(return_worker(
+            socket,
+            pipeline,
+            vrchat_socket.clone(),
+        )));
+    }
+    let clients = Clients::default();
+    if let Some(websocket) = configuration.websocket {
+        workers.push(spawn(websocket_worker(
+            websocket.listen,
+            clients.clone(),
+            vrchat_socket.clone(),
+        )));
+    }
+    let mqtt = match configuration.mqtt {
+        Some(mqtt_configuration) => {
+            let (bridge, worker) = MqttBridge::start(mqtt_configuration, vrchat_socket.clone())?;
+            workers.push(worker);
+            Some(bridge)
+        }
+        None => None,
+    };
+    workers.push(spawn(forward_worker(listen_socket, targets, clients, mqtt)));
+
+    try_join_all(workers).await?;
+    Ok(())
+}
+
+/// Receives packets from VRChat and distributes them to targets, WebSocket clients and MQTT.
+async fn forward_worker(
+    listen_socket: UdpSocket,
+    targets: Vec<Arc<Target>>,
+    clients: Clients,
+    mqtt: Option<Arc<MqttBridge>>,
+) -> Result<()> {
+    let mut buffer = vec![0; RECEIVE_BUFFER_SIZE];
+    loop {
+        let (length, source) = match listen_socket.recv_from(&mut buffer).await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("Failed to receive from VRChat: {}", e);
+                continue;
+            }
+        };
+        let packet = match OscPacket::deserialize(&buffer[..length]) {
+            Ok(p) => p,
+            Err(e) => {
+                warn!("Invalid OSC packet from {}: {}", source, e);
+                continue;
+            }
+        };
+
+        for target in &targets {
+            let transformed = match target.pipeline.transform(packet.clone()) {
+                Some(p) => p,
+                None => continue,
+            };
+            trace!("Relaying to \"{}\": {:?}", target.name, transformed);
+            if let Err(e) = target.socket.send(&transformed.serialize()).await {
+                warn!("Failed to relay to \"{}\": {}", target.name, e);
+            }
+        }
+        clients.broadcast(&packet).await;
+        if let Some(mqtt) = &mqtt {
+            mqtt.publish(packet);
+        }
+    }
+}
+
+/// Receives packets sent back from a bidirectional target and relays them to VRChat.
+async fn target_return_worker(target: Arc<Target>, vrchat_socket: Arc<UdpSocket>) -> Result<()> {
+    let pipeline = match &target.return_pipeline {
+        Some(p) => p,
+        None => return Ok(()),
+    };
+
+    let mut buffer = vec![0; RECEIVE_BUFFER_SIZE];
+    loop {
+        let length = match target.socket.recv(&mut buffer).await {
+            Ok(l) => l,
+            Err(e) => {
+                // Sending to closed port causes ICMP errors on some platforms.
+                trace!("Failed to receive from \"{}\": {}", target.name, e);
+                continue;
+            }
+        };
+        relay_to_vrchat(&buffer[..length], pipeline, &vrchat_socket, &target.name).await;
+    }
+}
+
+/// Receives packets from tools and relays them to VRChat.
+async fn return_worker(
+    socket: UdpSocket,
+    pipeline: OscPipeline,
+    vrchat_socket: Arc<UdpSocket>,
+) -> Result<()> {
+    let mut buffer = vec![0; RECEIVE_BUFFER_SIZE];
+    loop {
+        let (length, source) = match socket.recv_from(&mut buffer).await {
+            Ok(r) => r,
+            Err(e) => {
+                // Sending to closed port causes ICMP errors on some platforms.
+                warn!("Failed to receive from tools: {}", e);
+                continue;
+            }
+        };
+        relay_to_vrchat(
+            &buffer[..length],
+            &pipeline,
+            &vrchat_socket,
+            &source.to_string(),
+        )
+        .await;
+    }
+}
+
+async fn relay_to_vrchat(
+    bytes: &[u8],
+    pipeline: &OscPipeline,
+    vrchat_socket: &UdpSocket,
+    source: &str,
+) {
+    let packet = match OscPacket::deserialize(bytes) {
+        Ok(p) => p,
+        Err(e) => {
+            warn!("Invalid OSC packet from \"{}\": {}", source, e);
+            return;
+        }
+    };
+    let transformed = match pipeline.transform(packet) {
+        Some(p) => p,
+        None => return,
+    };
+
+    trace!("Relaying to VRChat from \"{}\": {:?}", source, transformed);
+    if let Err(e) = vrchat_socket.send(&transformed.serialize()).await {
+        warn!("Failed to relay to VRChat: {}", e);
+    }
+}
+
+/// Collects messages in the packet, including ones in nested bundles, in delivery order.
+pub fn flatten_messages(packet: OscPacket) -> Vec<OscMessage> {
+    match packet {
+        OscPacket::Message(m) => vec![m],
+        OscPacket::Bundle(b) => b.flatten().map(|(_, m)| m.clone()).collect(),
+    }
+}
+
+/// Binds a socket on the unspecified address of the same family as `address` and connects to it.
+async fn connected_socket(address: SocketAddr) -> Result<UdpSocket> {
+    let bind_addr: SocketAddr = match address {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(bind_addr).await?;
+    socket.connect(address).await?;
+    Ok(socket)
+}
+
+/// Builds a pipeline from transform descriptions.
+pub fn build_pipeline(transforms: Vec<TransformDescription>) -> Result<OscPipeline> {
+    let pipeline = PipelineDescription { transforms }.build()?;
+    Ok(pipeline)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{connected_socket, forward_worker, target_return_worker, Target};
+    use crate::{app::TargetConfiguration, websocket::Clients};
+
+    use std::time::Duration;
+
+    use async_std::{
+        future::timeout,
+        net::{Ipv4Addr, SocketAddr, UdpSocket},
+        sync::Arc,
+        task::spawn,
+    };
+    use phorcys_osc::{
+        prelude::*,
+        transform::{FilterAction, TransformDescription},
+    };
+
+    fn message(address: &str) -> OscPacket {
+        OscPacket::Message(
+            OscMessageBuilder::new(address)
+                .expect("Should valid")
+                .push_argument(OscValue::Int32(1))
+                .build(),
+        )
+    }
+
+    async fn bind_local() -> UdpSocket {
+        UdpSocket::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+            .await
+            .expect("Should bind")
+    }
+
+    async fn receive(socket: &UdpSocket) -> (OscPacket, SocketAddr) {
+        let mut buffer = vec![0; 1024];
+        let (length, source) = timeout(Duration::from_secs(5), socket.recv_from(&mut buffer))
+            .await
+            .expect("Should receive in time")
+            .expect("Should receive");
+        let packet = OscPacket::deserialize(&buffer[..length]).expect("Should be OSC");
+        (packet, source)
+    }
+
+    /// Ensures packets are filtered and rewritten per target, and returned packets reach VRChat.
+    #[async_std::test]
+    async fn test_relay_loopback() {
+        let vrchat = bind_local().await;
+        let listen = bind_local().await;
+        let listen_address = listen.local_addr().expect("Should be bound");
+        let filtered_tool = bind_local().await;
+        let rewritten_tool = bind_local().await;
+
+        let filtered = Target::new(TargetConfiguration {
+            name: "filtered".into(),
+            address: filtered_tool.local_addr().expect("Should be bound"),
+            transform: vec![TransformDescription::Filter {
+                action: FilterAction::Keep,
+                pattern: "/avatar/parameters/Foo".into(),
+            }],
+            bidirectional: true,
+            return_transform: vec![],
+        })
+        .await
+        .expect("Should create target");
+        let rewritten = Target::new(TargetConfiguration {
+            name: "rewritten".into(),
+            address: rewritten_tool.local_addr().expect("Should be bound"),
+            transform: vec![TransformDescription::Rewrite {
+                pattern: "/avatar/parameters/*".into(),
+                replacement: "/params/$1".into(),
+            }],
+            bidirectional: false,
+            return_transform: vec![],
+        })
+        .await
+        .expect("Should create target");
+        let filtered = Arc::new(filtered);
+        let vrchat_socket = Arc::new(
+            connected_socket(vrchat.local_addr().expect("Should be bound"))
+                .await
+                .expect("Should connect"),
+        );
+        spawn(target_return_worker(filtered.clone(), vrchat_socket));
+        spawn(forward_worker(
+            listen,
+            vec![filtered, Arc::new(rewritten)],
+            Clients::default(),
+            None,
+        ));
+
+        for address in ["/avatar/parameters/Bar", "/avatar/parameters/Foo"] {
+            vrchat
+                .send_to(&message(address).serialize(), listen_address)
+                .await
+                .expect("Should send");
+        }
+
+        let (packet, relay_address) = receive(&filtered_tool).await;
+        assert_eq!(packet, message("/avatar/parameters/Foo"));
+        assert_eq!(receive(&rewritten_tool).await.0, message("/params/Bar"));
+        assert_eq!(receive(&rewritten_tool).await.0, message("/params/Foo"));
+
+        filtered_tool
+            .send_to(
+                &message("/avatar/parameters/Baz").serialize(),
+                relay_address,
+            )
+            .await
+            .expect("Should send");
+        assert_eq!(receive(&vrchat).await.0, message("/avatar/parameters/Baz"));
+    }
+}
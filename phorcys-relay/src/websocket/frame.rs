@@ -0,0 +1,89 @@
+//! Frames between WebSocket clients.
+
+use crate::json::{value_from_json, value_to_json};
+
+use anyhow::Result;
+use phorcys_osc::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+/// Frame sent from clients.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientFrame {
+    /// Adds address patterns to receive.
+    Subscribe {
+        patterns: Vec<String>,
+        #[serde(default)]
+        format: FrameFormat,
+    },
+
+    /// Removes address patterns.
+    Unsubscribe { patterns: Vec<String> },
+
+    /// Message to send to VRChat.
+    Message {
+        address: String,
+        #[serde(default)]
+        arguments: Vec<JsonValue>,
+    },
+}
+
+/// Format of frames sent to clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameFormat {
+    /// JSON text frames, one for each message.
+    #[default]
+    Json,
+
+    /// Binary frames of OSC packets.
+    Binary,
+}
+
+/// Builds OSC message from JSON address and arguments.
+pub fn message_from_json(address: &str, arguments: &[JsonValue]) -> Result<OscMessage> {
+    let arguments = arguments
+        .iter()
+        .map(value_from_json)
+        .collect::<Result<Vec<_>>>()?;
+    let message = OscMessageBuilder::new(address)?
+        .set_arguments(arguments)
+        .build();
+    Ok(message)
+}
+
+/// Converts OSC message into JSON text.
+pub fn message_to_json(message: &OscMessage) -> String {
+    let arguments: Vec<_> = message.arguments().iter().map(value_to_json).collect();
+    json!({
+        "type": "message",
+        "address": message.address().as_str(),
+        "arguments": arguments,
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{message_from_json, message_to_json, ClientFrame};
+
+    /// Ensures message frames are parsed and written.
+    #[test]
+    fn test_message_frame() {
+        let frame: ClientFrame = serde_json::from_str(
+            r#"{ "type": "message", "address": "/avatar/parameters/Foo", "arguments": [true] }"#,
+        )
+        .expect("Should success");
+        let message = match frame {
+            ClientFrame::Message { address, arguments } => {
+                message_from_json(&address, &arguments).expect("Should valid")
+            }
+            _ => unreachable!("Should be message"),
+        };
+        assert_eq!(
+            message_to_json(&message),
+            r#"{"address":"/avatar/parameters/Foo","arguments":[true],"type":"message"}"#
+        );
+    }
+}
@@ -0,0 +1,235 @@
+//! WebSocket bridge for browser clients.
+//!
+//! Clients subscribe to address patterns with JSON text frames,
+//! and receive matching packets from VRChat as JSON text frames or binary OSC frames.
+//! Both JSON message frames and binary OSC frames from clients are relayed to VRChat.
+
+mod frame;
+
+use crate::{
+    relay::flatten_messages,
+    websocket::frame::{message_from_json, message_to_json, ClientFrame, FrameFormat},
+};
+
+use anyhow::Result;
+use async_std::{
+    net::{SocketAddr, TcpListener, TcpStream, UdpSocket},
+    sync::{Arc, Mutex},
+    task::spawn,
+};
+use async_tungstenite::{
+    accept_async,
+    tungstenite::{Message as WsMessage, Result as WsResult},
+};
+use futures::{
+    channel::mpsc::{channel, Receiver, Sender},
+    stream::select,
+    SinkExt, StreamExt,
+};
+use log::{info, trace, warn};
+use phorcys_osc::{prelude::*, transform::Transform};
+use regex::Regex;
+
+/// Number of packets queued for each client.
+const CLIENT_QUEUE_LENGTH: usize = 256;
+
+/// Senders to connected clients.
+#[derive(Debug, Clone, Default)]
+pub struct Clients(Arc<Mutex<Vec<Sender<OscPacket>>>>);
+
+impl Clients {
+    /// Sends a packet to all connected clients, and forgets disconnected ones.
+    /// Packets are dropped for clients whose queue is full.
+    pub async fn broadcast(&self, packet: &OscPacket) {
+        let mut senders = self.0.lock().await;
+        senders.retain_mut(|tx| match tx.try_send(packet.clone()) {
+            Ok(()) => true,
+            Err(e) if e.is_full() => {
+                trace!("WebSocket client queue is full, dropping packet");
+                true
+            }
+            Err(_) => false,
+        });
+    }
+
+    async fn register(&self) -> Receiver<OscPacket> {
+        let (tx, rx) = channel(CLIENT_QUEUE_LENGTH);
+        self.0.lock().await.push(tx);
+        rx
+    }
+}
+
+/// Address patterns a client subscribes to.
+#[derive(Debug, Default)]
+struct Subscriptions(Vec<(String, Regex)>);
+
+impl Transform for Subscriptions {
+    fn transform_message(&self, message: OscMessage) -> Option<OscMessage> {
+        let address = message.address().as_str();
+        if self.0.iter().any(|(_, regex)| regex.is_match(address)) {
+            Some(message)
+        } else {
+            None
+        }
+    }
+}
+
+/// Events processed in client loop.
+enum ClientEvent {
+    /// Frame from the client.
+    Frame(WsResult<WsMessage>),
+
+    /// Packet from VRChat.
+    Packet(OscPacket),
+}
+
+/// Accepts WebSocket clients.
+pub async fn websocket_worker(
+    listen: SocketAddr,
+    clients: Clients,
+    vrchat_socket: Arc<UdpSocket>,
+) -> Result<()> {
+    let listener = TcpListener::bind(listen).await?;
+    info!("Accepting WebSocket clients at {}", listen);
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let clients = clients.clone();
+        let vrchat_socket = vrchat_socket.clone();
+        spawn(async move {
+            match client_worker(stream, clients, vrchat_socket).await {
+                Ok(()) => info!("WebSocket client {} disconnected", peer),
+                Err(e) => warn!("WebSocket client {} aborted: {}", peer, e),
+            }
+        });
+    }
+}
+
+/// Processes a WebSocket client.
+async fn client_worker(
+    stream: TcpStream,
+    clients: Clients,
+    vrchat_socket: Arc<UdpSocket>,
+) -> Result<()> {
+    let websocket = accept_async(stream).await?;
+    let (mut sink, frames) = websocket.split();
+    let packets = clients.register().await;
+
+    let mut subscriptions = Subscriptions::default();
+    let mut format = FrameFormat::Json;
+    let mut events = select(
+        frames.map(ClientEvent::Frame),
+        packets.map(ClientEvent::Packet),
+    );
+
+    while let Some(event) = events.next().await {
+        let frame = match event {
+            ClientEvent::Packet(packet) => {
+                let packet = match subscriptions.transform(packet) {
+                    Some(p) => p,
+                    None => continue,
+                };
+                match format {
+                    FrameFormat::Binary => {
+                        sink.send(WsMessage::Binary(packet.serialize().into_vec()))
+                            .await?
+                    }
+                    FrameFormat::Json => {
+                        for message in flatten_messages(packet) {
+                            sink.send(WsMessage::Text(message_to_json(&message)))
+                                .await?;
+                        }
+                    }
+                }
+                continue;
+            }
+            ClientEvent::Frame(frame) => frame?,
+        };
+
+        match frame {
+            WsMessage::Text(text) => {
+                let frame: ClientFrame = match serde_json::from_str(&text) {
+                    Ok(f) => f,
+                    Err(e) => {
+                        warn!("Invalid frame from WebSocket client: {}", e);
+                        continue;
+                    }
+                };
+                match frame {
+                    ClientFrame::Subscribe {
+                        patterns,
+                        format: new_format,
+                    } => {
+                        for pattern in patterns {
+                            match OscAddressPattern::compile_to_regex(&pattern) {
+                                Ok(regex) => subscriptions.0.push((pattern, regex)),
+                                Err(e) => warn!("Invalid pattern \"{}\": {}", pattern, e),
+                            }
+                        }
+                        format = new_format;
+                    }
+                    ClientFrame::Unsubscribe { patterns } => {
+                        subscriptions.0.retain(|(p, _)| !patterns.contains(p));
+                    }
+                    ClientFrame::Message { address, arguments } => {
+                        match message_from_json(&address, &arguments) {
+                            Ok(message) => {
+                                send_to_vrchat(&vrchat_socket, OscPacket::Message(message)).await
+                            }
+                            Err(e) => warn!("Invalid message from WebSocket client: {}", e),
+                        }
+                    }
+                }
+            }
+            WsMessage::Binary(bytes) => match OscPacket::deserialize(&bytes) {
+                Ok(packet) => send_to_vrchat(&vrchat_socket, packet).await,
+                Err(e) => warn!("Invalid OSC packet from WebSocket client: {}", e),
+            },
+            WsMessage::Close(_) => break,
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+async fn send_to_vrchat(vrchat_socket: &UdpSocket, packet: OscPacket) {
+    trace!("Relaying to VRChat from WebSocket: {:?}", packet);
+    if let Err(e) = vrchat_socket.send(&packet.serialize()).await {
+        warn!("Failed to relay to VRChat: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Clients, CLIENT_QUEUE_LENGTH};
+
+    use futures::StreamExt;
+    use phorcys_osc::prelude::*;
+
+    /// Ensures packets to stalled clients are dropped and disconnected clients are forgotten.
+    #[async_std::test]
+    async fn test_clients_bounded() {
+        let packet = OscPacket::Message(
+            OscMessageBuilder::new("/avatar/parameters/Foo")
+                .expect("Should valid")
+                .build(),
+        );
+        let clients = Clients::default();
+        let mut stalled = clients.register().await;
+        let disconnected = clients.register().await;
+        drop(disconnected);
+
+        for _ in 0..(CLIENT_QUEUE_LENGTH * 4) {
+            clients.broadcast(&packet).await;
+        }
+        assert_eq!(clients.0.lock().await.len(), 1);
+
+        stalled.close();
+        let mut received = 0;
+        while stalled.next().await.is_some() {
+            received += 1;
+        }
+        assert!(received > 0 && received <= CLIENT_QUEUE_LENGTH + 1);
+    }
+}
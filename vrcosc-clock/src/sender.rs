@@ -132,7 +132,7 @@ pub async fn run_script(constructor: SenderConstructor) -> Result<()> {
 }
 
 /// Constructs conversion elements from Lua table.
-async fn convert_table<'lua>(filename: &str) -> Result<(Lua, Vec<ConversionElement>)> {
+async fn convert_table(filename: &str) -> Result<(Lua, Vec<ConversionElement>)> {
     let script = read_to_string(filename).await?;
     let lua = Lua::new();
     let elements_table: LuaTable = lua.load(&script).call(())?;